@@ -0,0 +1,3 @@
+-- SCAP benchmark published alongside the manual STIG, if one has been imported
+ALTER TABLE stigs_catalog ADD COLUMN IF NOT EXISTS scap_version      TEXT;
+ALTER TABLE stigs_catalog ADD COLUMN IF NOT EXISTS scap_release_info TEXT;
@@ -0,0 +1,9 @@
+-- Benchmark-level metadata from the XCCDF header
+ALTER TABLE stigs_catalog ADD COLUMN IF NOT EXISTS status       TEXT   NOT NULL DEFAULT '';
+ALTER TABLE stigs_catalog ADD COLUMN IF NOT EXISTS status_date  DATE;
+ALTER TABLE stigs_catalog ADD COLUMN IF NOT EXISTS notice       TEXT;
+ALTER TABLE stigs_catalog ADD COLUMN IF NOT EXISTS front_matter TEXT;
+ALTER TABLE stigs_catalog ADD COLUMN IF NOT EXISTS rear_matter  TEXT;
+ALTER TABLE stigs_catalog ADD COLUMN IF NOT EXISTS publisher    TEXT;
+ALTER TABLE stigs_catalog ADD COLUMN IF NOT EXISTS source       TEXT;
+ALTER TABLE stigs_catalog ADD COLUMN IF NOT EXISTS platforms    TEXT[] NOT NULL DEFAULT '{}';
@@ -0,0 +1,28 @@
+-- Release number, benchmark date and V#R# label derived from release_info
+ALTER TABLE stigs_catalog ADD COLUMN IF NOT EXISTS release_number INTEGER;
+ALTER TABLE stigs_catalog ADD COLUMN IF NOT EXISTS benchmark_date DATE;
+ALTER TABLE stigs_catalog ADD COLUMN IF NOT EXISTS release_label  TEXT;
+
+-- Backfill rows imported before these columns existed,
+-- e.g. "Release: 5 Benchmark Date: 05 Jan 2026"
+UPDATE stigs_catalog
+SET release_number = substring(release_info FROM 'Release:\s*(\d+)')::INTEGER
+WHERE release_number IS NULL
+  AND release_info ~ 'Release:\s*\d+';
+
+UPDATE stigs_catalog
+SET benchmark_date = to_date(
+        regexp_replace(release_info,
+            '^.*Benchmark Date:\s*(\d{1,2})\s+([A-Za-z]{3})[A-Za-z]*\s+(\d{4}).*$',
+            '\1 \2 \3'),
+        'DD Mon YYYY')
+WHERE benchmark_date IS NULL
+  AND release_info ~ 'Benchmark Date:\s*\d{1,2}\s+[A-Za-z]{3}[A-Za-z]*\s+\d{4}';
+
+UPDATE stigs_catalog
+SET release_label = 'V' || (version::INTEGER) || 'R' || release_number
+WHERE release_label IS NULL
+  AND release_number IS NOT NULL
+  AND version ~ '^\s*\d+\s*$';
+
+CREATE INDEX IF NOT EXISTS idx_stigs_catalog_benchmark_date ON stigs_catalog (benchmark_date);
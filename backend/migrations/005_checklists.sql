@@ -0,0 +1,16 @@
+-- XCCDF Benchmark id, used to match imported checklists to catalog entries
+ALTER TABLE stigs_catalog ADD COLUMN IF NOT EXISTS benchmark_id TEXT NOT NULL DEFAULT '';
+CREATE INDEX IF NOT EXISTS idx_stigs_catalog_benchmark ON stigs_catalog (benchmark_id, version);
+
+-- Reviewed checklists; the full checklist lives in json_path
+CREATE TABLE IF NOT EXISTS checklists (
+    id            TEXT PRIMARY KEY,
+    name          TEXT        NOT NULL,
+    host_name     TEXT        NOT NULL DEFAULT '',
+    source_format TEXT        NOT NULL,
+    stig_ids      TEXT[]      NOT NULL DEFAULT '{}',
+    rule_count    INTEGER     NOT NULL DEFAULT 0,
+    json_path     TEXT        NOT NULL,
+    created_at    TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at    TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,3 @@
+-- Tags assigned by taxonomy.toml, e.g. {Network,Firewall}
+ALTER TABLE stigs_catalog ADD COLUMN IF NOT EXISTS tags TEXT[] NOT NULL DEFAULT '{}';
+CREATE INDEX IF NOT EXISTS idx_stigs_catalog_tags ON stigs_catalog USING GIN (tags);
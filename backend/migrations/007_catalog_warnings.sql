@@ -0,0 +1,2 @@
+-- Parser warnings for the imported benchmark, e.g. rules missing fix text
+ALTER TABLE stigs_catalog ADD COLUMN IF NOT EXISTS warnings JSONB NOT NULL DEFAULT '[]';
@@ -0,0 +1,3 @@
+-- parser::PARSER_VERSION of the parse behind each entry's JSON; entries
+-- imported before it was recorded are stale until re-imported
+ALTER TABLE stigs_catalog ADD COLUMN IF NOT EXISTS parser_version INTEGER NOT NULL DEFAULT 0;
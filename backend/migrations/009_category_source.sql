@@ -0,0 +1,4 @@
+-- Where each entry's category came from: 'taxonomy', or 'manifest' / 'upload'
+-- for categories POST /api/catalog/recategorize must leave alone.  Synced
+-- entries are marked 'manifest' again by the first sync after upgrading.
+ALTER TABLE stigs_catalog ADD COLUMN IF NOT EXISTS category_source TEXT NOT NULL DEFAULT 'taxonomy';
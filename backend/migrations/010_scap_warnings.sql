@@ -0,0 +1,2 @@
+-- Parser warnings for the SCAP benchmark recorded by set_scap_benchmark
+ALTER TABLE stigs_catalog ADD COLUMN IF NOT EXISTS scap_warnings JSONB NOT NULL DEFAULT '[]';
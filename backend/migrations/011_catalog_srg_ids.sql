@@ -0,0 +1,3 @@
+-- SRG ids of the entry's rules, looked up by GET /api/srg/:srg_id
+ALTER TABLE stigs_catalog ADD COLUMN IF NOT EXISTS srg_ids TEXT[] NOT NULL DEFAULT '{}';
+CREATE INDEX IF NOT EXISTS idx_stigs_catalog_srg_ids ON stigs_catalog USING GIN (srg_ids);
@@ -3,22 +3,63 @@ use axum::{
     http::StatusCode,
     Json,
 };
+use chrono::NaiveDate;
 use serde::Deserialize;
+use std::sync::PoisonError;
 
-use crate::db::{count_catalog, list_catalog};
+use crate::db::{count_catalog, list_catalog, set_catalog_category, CatalogFilter, CatalogSort};
+use crate::taxonomy::{load_taxonomy, CategorySource};
 use crate::AppState;
 
 #[derive(Debug, Deserialize)]
 pub struct CatalogQuery {
     pub category: Option<String>,
+    /// Only entries carrying this tag, e.g. `Firewall`.
+    pub tag: Option<String>,
+    /// Only benchmarks dated on or after / on or before this day (YYYY-MM-DD).
+    pub released_after: Option<NaiveDate>,
+    pub released_before: Option<NaiveDate>,
+    /// `true` for only the entries that need re-importing because they were
+    /// parsed by an older release.
+    #[serde(default)]
+    pub stale: bool,
+    /// `title` (default), `benchmark_date`, `release_number` or `last_updated`.
+    pub sort: Option<String>,
+    /// `asc` (default) or `desc`.
+    pub order: Option<String>,
 }
 
-/// GET /api/catalog[?category=Windows]
+/// GET /api/catalog[?category=Windows][&tag=Firewall][&released_after=2025-10-01]
+///                 [&released_before=…][&stale=true]
+///                 [&sort=benchmark_date][&order=desc]
 pub async fn get_catalog(
     State(state): State<AppState>,
     Query(params): Query<CatalogQuery>,
 ) -> Result<impl axum::response::IntoResponse, StatusCode> {
-    let entries = list_catalog(&state.pool, params.category.as_deref())
+    let sort = match params.sort.as_deref() {
+        None | Some("title") => CatalogSort::Title,
+        Some("benchmark_date") => CatalogSort::BenchmarkDate,
+        Some("release_number") => CatalogSort::ReleaseNumber,
+        Some("last_updated") => CatalogSort::LastUpdated,
+        Some(_) => return Err(StatusCode::BAD_REQUEST),
+    };
+    let descending = match params.order.as_deref() {
+        None | Some("asc") => false,
+        Some("desc") => true,
+        Some(_) => return Err(StatusCode::BAD_REQUEST),
+    };
+    let filter = CatalogFilter {
+        category: params.category,
+        tag: params.tag,
+        released_after: params.released_after,
+        released_before: params.released_before,
+        stale: params.stale,
+        srg_id: None,
+        sort,
+        descending,
+    };
+
+    let entries = list_catalog(&state.pool, &filter)
         .await
         .map_err(|e| {
             tracing::error!("catalog query failed: {e:#}");
@@ -27,6 +68,56 @@ pub async fn get_catalog(
     Ok(Json(entries))
 }
 
+/// POST /api/catalog/recategorize
+///
+/// Reloads taxonomy.toml and re-runs it over every catalog entry, replacing
+/// each entry's tags and, unless the manifest or an upload pinned it, its
+/// category.  Responds with the entries that changed.
+///
+/// Example:
+///   curl -X POST http://localhost:8080/api/catalog/recategorize
+pub async fn recategorize_catalog(
+    State(state): State<AppState>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let taxonomy = load_taxonomy()
+        .map_err(|e| (StatusCode::UNPROCESSABLE_ENTITY, format!("{e:#}")))?;
+
+    let entries = list_catalog(&state.pool, &CatalogFilter::default())
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("{e:#}")))?;
+
+    let mut changed: Vec<serde_json::Value> = Vec::new();
+    for entry in &entries {
+        let mut classification = taxonomy.classify(&entry.title);
+        if entry.category_source != CategorySource::Taxonomy {
+            classification = classification.pin(entry.category.clone(), entry.category_source);
+        }
+        if classification.category == entry.category && classification.tags == entry.tags {
+            continue;
+        }
+        set_catalog_category(&state.pool, &entry.id, &classification.category, &classification.tags)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("{e:#}")))?;
+        changed.push(serde_json::json!({
+            "id": entry.id,
+            "title": entry.title,
+            "previousCategory": entry.category,
+            "category": classification.category,
+            "tags": classification.tags,
+        }));
+    }
+
+    // Later uploads and syncs use the reloaded rules too
+    *state.taxonomy.write().unwrap_or_else(PoisonError::into_inner) = taxonomy;
+
+    tracing::info!("Recategorized catalog: {} of {} entries changed", changed.len(), entries.len());
+    Ok(Json(serde_json::json!({
+        "total": entries.len(),
+        "changed": changed.len(),
+        "entries": changed,
+    })))
+}
+
 /// GET /api/health
 pub async fn get_health(State(state): State<AppState>) -> Json<serde_json::Value> {
     let count = count_catalog(&state.pool).await.unwrap_or(0);
@@ -0,0 +1,736 @@
+use axum::{
+    extract::{Multipart, Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use super::attachment;
+use crate::{
+    db::{find_catalog_stig, get_checklist_entry, list_checklists, ChecklistEntry},
+    export::{
+        ckl::checklist_to_ckl,
+        cklb::checklist_to_cklb,
+        poam::{self, checklist_to_poam, PoamOptions},
+        xccdf::checklist_to_xccdf,
+    },
+    parser::{
+        ckl::{parse_ckl_reader, Asset, Checklist, ChecklistStig, Review},
+        cklb::parse_cklb_reader,
+        results::{parse_results_reader, status_from_result, RuleResult, TestResult},
+        StigData,
+    },
+    store::save_checklist,
+    AppState,
+};
+
+/// POST /api/checklists/import
+///
+/// Accepts a multipart form upload with the following fields:
+///   file — a STIG Viewer 2 / Evaluate-STIG `.ckl` or STIG Viewer 3 `.cklb` (required)
+///   name — display name (optional; defaults to the asset's host name)
+///
+/// Each STIG in the checklist is linked to the catalog entry with the same
+/// benchmark id and version; `catalogId` is null for STIGs not in the catalog.
+///
+/// Example:
+///   curl -X POST http://localhost:8080/api/checklists/import \
+///        -F "file=@web01_Kubernetes.ckl"
+pub async fn import_checklist(
+    State(state): State<AppState>,
+    mut multipart: Multipart,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let mut file: Option<(String, Vec<u8>)> = None;
+    let mut name: Option<String> = None;
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Multipart error: {e}")))?
+    {
+        match field.name() {
+            Some("file") => {
+                let file_name = field.file_name().unwrap_or_default().to_string();
+                let bytes = field.bytes().await.map_err(|e| {
+                    (
+                        StatusCode::BAD_REQUEST,
+                        format!("Failed to read file field: {e}"),
+                    )
+                })?;
+                file = Some((file_name, bytes.to_vec()));
+            }
+            Some("name") => {
+                let text = field.text().await.map_err(|e| {
+                    (
+                        StatusCode::BAD_REQUEST,
+                        format!("Failed to read name field: {e}"),
+                    )
+                })?;
+                name = Some(text.trim().to_string()).filter(|n| !n.is_empty());
+            }
+            _ => {} // ignore unknown fields
+        }
+    }
+
+    let (file_name, bytes) =
+        file.ok_or((StatusCode::BAD_REQUEST, "Missing 'file' field".into()))?;
+
+    // CKLB is JSON; sniff the content as well as the extension
+    let content = bytes.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(&bytes);
+    let is_cklb = file_name.to_lowercase().ends_with(".cklb")
+        || content.iter().find(|b| !b.is_ascii_whitespace()) == Some(&b'{');
+    let (source_format, parsed) = if is_cklb {
+        ("cklb", parse_cklb_reader(content))
+    } else {
+        ("ckl", parse_ckl_reader(content))
+    };
+    let mut checklist = parsed.map_err(|e| {
+        (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!(
+                "{} parse failed ({file_name}): {e:#}",
+                source_format.to_uppercase()
+            ),
+        )
+    })?;
+
+    for stig in &mut checklist.stigs {
+        stig.catalog_id = find_catalog_stig(
+            &state.pool,
+            &stig.stig.benchmark_id,
+            &stig.stig.title,
+            &stig.stig.version,
+            &stig.stig.release_info,
+        )
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("{e:#}")))?;
+
+        // CKL has no rule uuids; assign them once so every CKLB export matches
+        stig.uuid
+            .get_or_insert_with(|| uuid::Uuid::new_v4().to_string());
+        for rule in &mut stig.stig.rules {
+            rule.uuid
+                .get_or_insert_with(|| uuid::Uuid::new_v4().to_string());
+        }
+    }
+
+    let name = name
+        .or_else(|| Some(checklist.asset.host_name.clone()).filter(|n| !n.is_empty()))
+        .or_else(|| Some(file_name.clone()).filter(|n| !n.is_empty()))
+        .unwrap_or_else(|| checklist.stigs[0].stig.title.clone());
+
+    let checklists_dir = state.config.data_dir.join("checklists");
+    tokio::fs::create_dir_all(&checklists_dir)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to create data dir: {e}"),
+            )
+        })?;
+
+    let id = uuid::Uuid::new_v4().to_string();
+    let entry = save_checklist(
+        &state.pool,
+        &checklists_dir,
+        &id,
+        &name,
+        source_format,
+        &checklist,
+    )
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("{e:#}")))?;
+
+    tracing::info!(
+        "Imported checklist '{id}' ({name}): {} STIGs, {} rules",
+        checklist.stigs.len(),
+        entry.rule_count
+    );
+
+    let stigs: Vec<serde_json::Value> = checklist
+        .stigs
+        .iter()
+        .map(|s| {
+            serde_json::json!({
+                "benchmarkId": s.stig.benchmark_id,
+                "title": s.stig.title,
+                "version": s.stig.version,
+                "releaseInfo": s.stig.release_info,
+                "catalogId": s.catalog_id,
+                "ruleCount": s.stig.rules.len(),
+            })
+        })
+        .collect();
+
+    Ok(Json(serde_json::json!({
+        "id": entry.id,
+        "name": entry.name,
+        "hostName": entry.host_name,
+        "ruleCount": entry.rule_count,
+        "stigs": stigs,
+    })))
+}
+
+/// GET /api/checklists
+pub async fn get_checklists(
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let entries = list_checklists(&state.pool).await.map_err(|e| {
+        tracing::error!("checklist query failed: {e:#}");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(Json(entries))
+}
+
+/// GET /api/checklists/:id
+///
+/// Returns the stored checklist: asset plus each STIG with its reviewed rules.
+pub async fn get_checklist(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, StatusCode> {
+    // Sanitise the id — only allow alphanumeric + hyphens to prevent path traversal
+    if !id.chars().all(|c| c.is_alphanumeric() || c == '-') {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let path = state
+        .config
+        .data_dir
+        .join("checklists")
+        .join(format!("{id}.json"));
+
+    let contents = tokio::fs::read_to_string(&path).await.map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            StatusCode::NOT_FOUND
+        } else {
+            tracing::error!("Failed to read {}: {e:#}", path.display());
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    })?;
+
+    let value: serde_json::Value = serde_json::from_str(&contents).map_err(|e| {
+        tracing::error!("Failed to deserialise {}: {e:#}", path.display());
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(Json(value))
+}
+
+/// GET /api/checklists/:id/export.ckl
+///
+/// Downloads the checklist as a STIG Viewer 2 `.ckl`.
+pub async fn export_ckl(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let (entry, checklist) = load_checklist(&state, &id).await?;
+    let body = checklist_to_ckl(&checklist).into_bytes();
+    Ok(attachment(&entry.name, "ckl", "application/xml", body))
+}
+
+/// GET /api/checklists/:id/export.cklb
+///
+/// Downloads the checklist as a STIG Viewer 3 `.cklb`.
+pub async fn export_cklb(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, StatusCode> {
+    let (entry, checklist) = load_checklist(&state, &id).await?;
+    let cklb = checklist_to_cklb(&entry.id, &entry.name, &checklist);
+    let body = serde_json::to_vec_pretty(&cklb).map_err(|e| {
+        tracing::error!("Failed to serialise checklist {id}: {e:#}");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok(attachment(&entry.name, "cklb", "application/json", body))
+}
+
+/// Query parameters for GET /api/checklists/:id/export.xccdf.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct XccdfExportQuery {
+    /// Catalog id or benchmark id of the STIG to export; required when the
+    /// checklist holds more than one.
+    pub stig: Option<String>,
+    /// Reviewer identity, overriding the one recorded on the checklist.
+    pub identity: Option<String>,
+}
+
+/// GET /api/checklists/:id/export.xccdf
+///
+/// Downloads one STIG of the checklist as an XCCDF 1.2 `TestResult`. The
+/// reviewer and review times come from the checklist's recorded review
+/// (e.g. an imported SCC scan); without one, the checklist's creation and
+/// last update times are used.  A STIG imported without a benchmark id
+/// (`stigid`) cannot be referenced and is rejected with 422.
+///
+/// Example:
+///   curl -o k8s-cp01.xml \
+///        "http://localhost:8080/api/checklists/<id>/export.xccdf?identity=CORP%5Cjdoe"
+pub async fn export_xccdf(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(params): Query<XccdfExportQuery>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let (entry, checklist) = load_checklist(&state, &id)
+        .await
+        .map_err(|status| (status, format!("Checklist '{id}' could not be loaded")))?;
+
+    let stig = match params.stig.as_deref() {
+        Some(wanted) => checklist
+            .stigs
+            .iter()
+            .find(|s| s.catalog_id.as_deref() == Some(wanted) || s.stig.benchmark_id == wanted)
+            .ok_or_else(|| {
+                (
+                    StatusCode::NOT_FOUND,
+                    format!("Checklist has no STIG '{wanted}'"),
+                )
+            })?,
+        None if checklist.stigs.len() == 1 => &checklist.stigs[0],
+        None => {
+            let ids: Vec<&str> = checklist
+                .stigs
+                .iter()
+                .map(|s| s.stig.benchmark_id.as_str())
+                .collect();
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!(
+                    "Checklist has {} STIGs; choose one with ?stig= ({})",
+                    ids.len(),
+                    ids.join(", ")
+                ),
+            ));
+        }
+    };
+
+    // The TestResult id and its benchmark reference are built from it
+    if stig.stig.benchmark_id.trim().is_empty() {
+        return Err((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!(
+                "'{}' has no benchmark id to reference in XCCDF results",
+                stig.stig.title
+            ),
+        ));
+    }
+
+    let timestamp = |time: DateTime<Utc>| time.to_rfc3339_opts(SecondsFormat::Secs, true);
+    let recorded = checklist.review.clone().unwrap_or_default();
+    let review = Review {
+        identity: params.identity.unwrap_or(recorded.identity),
+        start_time: recorded
+            .start_time
+            .or_else(|| Some(timestamp(entry.created_at))),
+        end_time: recorded
+            .end_time
+            .or_else(|| Some(timestamp(entry.updated_at))),
+    };
+
+    let body = checklist_to_xccdf(&checklist.asset, stig, &review).into_bytes();
+    Ok(attachment(&entry.name, "xml", "application/xml", body))
+}
+
+/// GET /api/checklists/:id/poam.xlsx
+///
+/// Downloads an eMASS POA&M workbook with an item for every Open rule. The
+/// system information block is filled from the query string (`system`,
+/// `exportedBy`, `component`, `office`, `pocName`, `pocPhone`, `pocEmail`);
+/// `includeNotReviewed=true` also lists rules that have not been reviewed.
+/// `milestone` and `status` (an eMASS POA&M status such as `Ongoing`) are
+/// written to every item; without them those columns are left empty.
+///
+/// Example:
+///   curl -o poam.xlsx \
+///        "http://localhost:8080/api/checklists/<id>/poam.xlsx?system=Cluster%20A&office=ISSO"
+pub async fn export_poam(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Query(mut options): Query<PoamOptions>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    if let Some(status) = options.status.as_deref() {
+        if !poam::STATUSES.contains(&status) {
+            return Err((
+                StatusCode::UNPROCESSABLE_ENTITY,
+                format!(
+                    "Unknown POA&M status '{status}'; use one of {}",
+                    poam::STATUSES.join(", ")
+                ),
+            ));
+        }
+    }
+    let (entry, checklist) = load_checklist(&state, &id)
+        .await
+        .map_err(|status| (status, format!("Checklist '{id}' could not be loaded")))?;
+    options.system.get_or_insert_with(|| entry.name.clone());
+
+    let today = Utc::now().date_naive();
+    let body = checklist_to_poam(&checklist, &options, &state.cci, today).map_err(|e| {
+        tracing::error!("Failed to build POA&M for checklist {id}: {e:#}");
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to build POA&M workbook".to_string(),
+        )
+    })?;
+    Ok(attachment(
+        &format!("{}_POAM", entry.name),
+        "xlsx",
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        body,
+    ))
+}
+
+/// Read a checklist's row and its stored JSON.
+async fn load_checklist(
+    state: &AppState,
+    id: &str,
+) -> Result<(ChecklistEntry, Checklist), StatusCode> {
+    if !id.chars().all(|c| c.is_alphanumeric() || c == '-') {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    let entry = get_checklist_entry(&state.pool, id)
+        .await
+        .map_err(|e| {
+            tracing::error!("checklist query failed: {e:#}");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let path = state
+        .config
+        .data_dir
+        .join("checklists")
+        .join(format!("{id}.json"));
+    let contents = tokio::fs::read_to_string(&path).await.map_err(|e| {
+        tracing::error!("Failed to read {}: {e:#}", path.display());
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    let checklist = serde_json::from_str(&contents).map_err(|e| {
+        tracing::error!("Failed to deserialise {}: {e:#}", path.display());
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    Ok((entry, checklist))
+}
+
+/// POST /api/checklists/import/xccdf
+///
+/// Applies SCAP Compliance Checker XCCDF results to a checklist. Accepts a
+/// multipart form upload with the following fields:
+///   file      — the SCC `*_XCCDF-Results_*.xml` (required)
+///   checklist — id of a stored checklist to update (optional; a new
+///               checklist is created from the scan's target otherwise)
+///   stig      — catalog id of the scanned STIG (optional; matched on the
+///               results' benchmark id and version otherwise)
+///   name      — display name for a new checklist (optional)
+///
+/// Each `rule-result` is mapped onto the catalog STIG's rule with the same
+/// Rule id: pass → not_a_finding, fail → open, notapplicable →
+/// not_applicable. Rules the scan could not decide are left untouched and
+/// listed under `manualReview`.
+///
+/// Example:
+///   curl -X POST http://localhost:8080/api/checklists/import/xccdf \
+///        -F "file=@WEB01_SCC-5.10_Kubernetes_XCCDF-Results.xml"
+pub async fn import_results(
+    State(state): State<AppState>,
+    mut multipart: Multipart,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let mut file: Option<(String, Vec<u8>)> = None;
+    let mut checklist_id: Option<String> = None;
+    let mut catalog_id: Option<String> = None;
+    let mut name: Option<String> = None;
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| (StatusCode::BAD_REQUEST, format!("Multipart error: {e}")))?
+    {
+        let field_name = field.name().unwrap_or_default().to_string();
+        if field_name == "file" {
+            let file_name = field.file_name().unwrap_or_default().to_string();
+            let bytes = field.bytes().await.map_err(|e| {
+                (
+                    StatusCode::BAD_REQUEST,
+                    format!("Failed to read file field: {e}"),
+                )
+            })?;
+            file = Some((file_name, bytes.to_vec()));
+            continue;
+        }
+        let slot = match field_name.as_str() {
+            "checklist" => &mut checklist_id,
+            "stig" => &mut catalog_id,
+            "name" => &mut name,
+            _ => continue, // ignore unknown fields
+        };
+        let text = field.text().await.map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                format!("Failed to read {field_name} field: {e}"),
+            )
+        })?;
+        *slot = Some(text.trim().to_string()).filter(|t| !t.is_empty());
+    }
+
+    let (file_name, bytes) =
+        file.ok_or((StatusCode::BAD_REQUEST, "Missing 'file' field".into()))?;
+    let results = parse_results_reader(bytes.as_slice()).map_err(|e| {
+        (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            format!("XCCDF results parse failed ({file_name}): {e:#}"),
+        )
+    })?;
+
+    let catalog_id = match catalog_id {
+        Some(id) => id,
+        None => find_catalog_stig(
+            &state.pool,
+            &results.benchmark_id,
+            &results.benchmark_title,
+            &results.version,
+            "",
+        )
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("{e:#}")))?
+        .ok_or_else(|| {
+            (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                format!(
+                    "No catalog STIG matches benchmark '{}' version {}; pass its catalog id as 'stig'",
+                    results.benchmark_id, results.version
+                ),
+            )
+        })?,
+    };
+    let stig = load_catalog_stig(&state, &catalog_id).await?;
+
+    let (id, existing, mut checklist) = match checklist_id {
+        Some(id) => {
+            let (entry, checklist) = load_checklist(&state, &id)
+                .await
+                .map_err(|status| (status, format!("Checklist '{id}' could not be loaded")))?;
+            (id, Some(entry), checklist)
+        }
+        None => (
+            uuid::Uuid::new_v4().to_string(),
+            None,
+            Checklist {
+                asset: asset_from_results(&results),
+                ..Default::default()
+            },
+        ),
+    };
+
+    // The scan is the latest review of the asset
+    checklist.review = Some(Review {
+        identity: results.identity.clone(),
+        start_time: results.start_time.clone(),
+        end_time: results.end_time.clone(),
+    });
+
+    let position = checklist.stigs.iter().position(|s| {
+        s.catalog_id.as_deref() == Some(catalog_id.as_str())
+            || (!stig.benchmark_id.is_empty() && s.stig.benchmark_id == stig.benchmark_id)
+    });
+    let index = match position {
+        Some(index) => index,
+        None => {
+            let mut stig = stig;
+            for rule in &mut stig.rules {
+                rule.uuid
+                    .get_or_insert_with(|| uuid::Uuid::new_v4().to_string());
+                rule.status = "not_reviewed".to_string();
+            }
+            checklist.stigs.push(ChecklistStig {
+                catalog_id: Some(catalog_id.clone()),
+                uuid: Some(uuid::Uuid::new_v4().to_string()),
+                stig,
+                ..Default::default()
+            });
+            checklist.stigs.len() - 1
+        }
+    };
+
+    let mut by_rule: HashMap<&str, &RuleResult> = HashMap::new();
+    for result in &results.rule_results {
+        by_rule.insert(&result.rule_id, result);
+        // Scans of an older revision still identify the rule by its SV number
+        by_rule.entry(rule_base(&result.rule_id)).or_insert(result);
+    }
+
+    let mut applied = 0;
+    let mut matched: Vec<&str> = Vec::new();
+    let mut manual_review: Vec<serde_json::Value> = Vec::new();
+    for rule in &mut checklist.stigs[index].stig.rules {
+        let result = by_rule
+            .get(rule.id.as_str())
+            .or_else(|| by_rule.get(rule_base(&rule.id)))
+            .copied();
+        if let Some(result) = result {
+            matched.push(&result.rule_id);
+        }
+        match result.and_then(|r| status_from_result(&r.result).map(|status| (r, status))) {
+            Some((result, status)) => {
+                rule.status = status.to_string();
+                if rule.finding_details.is_empty()
+                    || rule.finding_details.starts_with(SCAN_DETAILS_PREFIX)
+                {
+                    rule.finding_details = match &result.time {
+                        Some(time) => format!("{SCAN_DETAILS_PREFIX}{} ({time})", result.result),
+                        None => format!("{SCAN_DETAILS_PREFIX}{}", result.result),
+                    };
+                }
+                applied += 1;
+            }
+            None if rule.status == "not_reviewed" || rule.status.is_empty() => {
+                manual_review.push(serde_json::json!({
+                    "ruleId": rule.id,
+                    "stigId": rule.stig_id,
+                    "groupId": rule.group_id,
+                    "title": rule.title,
+                    "severity": rule.severity,
+                    "result": result.map(|r| r.result.as_str()),
+                }));
+            }
+            None => {}
+        }
+    }
+    let unmatched: Vec<&str> = results
+        .rule_results
+        .iter()
+        .map(|r| r.rule_id.as_str())
+        .filter(|id| !matched.contains(id))
+        .collect();
+
+    let (name, source_format) = match &existing {
+        Some(entry) => (
+            name.unwrap_or_else(|| entry.name.clone()),
+            entry.source_format.clone(),
+        ),
+        None => (
+            name.or_else(|| Some(checklist.asset.host_name.clone()).filter(|n| !n.is_empty()))
+                .unwrap_or(file_name),
+            "xccdf".to_string(),
+        ),
+    };
+
+    let checklists_dir = state.config.data_dir.join("checklists");
+    tokio::fs::create_dir_all(&checklists_dir)
+        .await
+        .map_err(|e| {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to create data dir: {e}"),
+            )
+        })?;
+    let entry = save_checklist(
+        &state.pool,
+        &checklists_dir,
+        &id,
+        &name,
+        &source_format,
+        &checklist,
+    )
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("{e:#}")))?;
+
+    tracing::info!(
+        "Applied {applied} of {} XCCDF results to checklist '{id}' ({name}); {} rules need manual review",
+        results.rule_results.len(),
+        manual_review.len()
+    );
+
+    Ok(Json(serde_json::json!({
+        "id": entry.id,
+        "name": entry.name,
+        "hostName": entry.host_name,
+        "catalogId": catalog_id,
+        "benchmarkId": results.benchmark_id,
+        "version": results.version,
+        "applied": applied,
+        "manualReview": manual_review,
+        "unmatchedResults": unmatched,
+    })))
+}
+
+/// Finding details written for rules decided by a scan; a later scan may
+/// replace them, but details entered by a reviewer are left alone.
+const SCAN_DETAILS_PREFIX: &str = "SCAP Compliance Checker result: ";
+
+/// `SV-242376` for `SV-242376r960759_rule`: the rule without its revision.
+fn rule_base(rule_id: &str) -> &str {
+    match rule_id.strip_prefix("SV-").and_then(|rest| rest.find('r')) {
+        Some(pos) => &rule_id[..3 + pos],
+        None => rule_id,
+    }
+}
+
+/// Asset fields from the scan's target facts, falling back to `<target>`
+/// and `<target-address>`.
+fn asset_from_results(results: &TestResult) -> Asset {
+    const FACT: &str = "urn:scap:fact:asset:identifier:";
+    let fact = |name: &str| results.fact(&format!("{FACT}{name}")).map(str::to_string);
+    let host_name = fact("host_name").unwrap_or_else(|| results.target.clone());
+    let host_fqdn = fact("fqdn").unwrap_or_default();
+    Asset {
+        role: "None".to_string(),
+        asset_type: "Computing".to_string(),
+        host_ip: fact("ipv4")
+            .or_else(|| results.target_addresses.first().cloned())
+            .unwrap_or_default(),
+        host_mac: fact("mac").unwrap_or_default(),
+        host_name,
+        host_fqdn,
+        ..Default::default()
+    }
+}
+
+/// Read a catalog STIG's stored JSON.
+async fn load_catalog_stig(state: &AppState, id: &str) -> Result<StigData, (StatusCode, String)> {
+    if !id.chars().all(|c| c.is_alphanumeric() || c == '-') {
+        return Err((StatusCode::BAD_REQUEST, format!("Invalid STIG id '{id}'")));
+    }
+    let path = state
+        .config
+        .data_dir
+        .join("stigs")
+        .join(format!("{id}.json"));
+    let contents = tokio::fs::read_to_string(&path).await.map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            (StatusCode::NOT_FOUND, format!("STIG '{id}' not found"))
+        } else {
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to read {}: {e}", path.display()),
+            )
+        }
+    })?;
+    serde_json::from_str(&contents).map_err(|e| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Failed to deserialise {}: {e}", path.display()),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rule_base_drops_the_revision() {
+        assert_eq!(rule_base("SV-242376r960759_rule"), "SV-242376");
+        assert_eq!(rule_base("SV-1r1_rule"), "SV-1");
+    }
+
+    #[test]
+    fn rule_base_keeps_ids_without_a_revision() {
+        assert_eq!(rule_base("SV-242376"), "SV-242376");
+        assert_eq!(rule_base("V-242376"), "V-242376");
+        assert_eq!(rule_base("xccdf_rule_r1"), "xccdf_rule_r1");
+        assert_eq!(rule_base(""), "");
+    }
+}
@@ -1,3 +1,22 @@
+use axum::{http::header, response::IntoResponse};
+
 pub mod catalog;
+pub mod checklist;
+pub mod srg;
 pub mod stig;
 pub mod upload;
+
+/// A file download named `<name>.<extension>`, e.g. `k8s-cp01.cklb`.
+pub(crate) fn attachment(name: &str, extension: &str, content_type: &'static str, body: Vec<u8>) -> impl IntoResponse {
+    let stem: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' { c } else { '_' })
+        .collect();
+    (
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{stem}.{extension}\"")),
+        ],
+        body,
+    )
+}
@@ -0,0 +1,82 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use serde::Serialize;
+
+use crate::db::{list_catalog, CatalogFilter};
+use crate::parser::StigData;
+use crate::AppState;
+
+/// One catalog rule that implements the requested SRG requirement.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SrgRule {
+    pub catalog_id: String,
+    pub stig_title: String,
+    pub rule_id: String,
+    pub stig_id: String,
+    pub group_id: String,
+    pub title: String,
+    pub severity: String,
+}
+
+/// GET /api/srg/:srg_id
+///
+/// Lists every rule across the catalog whose Group title is the given SRG id,
+/// e.g. `SRG-OS-000480-GPOS-00227`.  Only the JSON of catalog entries whose
+/// `srg_ids` hold it is read.
+pub async fn get_srg_rules(
+    State(state): State<AppState>,
+    Path(srg_id): Path<String>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    if !srg_id.chars().all(|c| c.is_alphanumeric() || c == '-') {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let filter = CatalogFilter { srg_id: Some(srg_id.clone()), ..Default::default() };
+    let entries = list_catalog(&state.pool, &filter).await.map_err(|e| {
+        tracing::error!("catalog query failed: {e:#}");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let stigs_dir = state.config.data_dir.join("stigs");
+    let mut rules: Vec<SrgRule> = Vec::new();
+
+    for entry in entries {
+        let path = stigs_dir.join(format!("{}.json", entry.id));
+        let stig: StigData = match tokio::fs::read_to_string(&path)
+            .await
+            .map_err(anyhow::Error::from)
+            .and_then(|s| serde_json::from_str(&s).map_err(anyhow::Error::from))
+        {
+            Ok(stig) => stig,
+            Err(e) => {
+                tracing::warn!("Skipping {} in SRG lookup: {e:#}", path.display());
+                continue;
+            }
+        };
+
+        rules.extend(
+            stig.rules
+                .into_iter()
+                .filter(|r| r.srg_id.as_deref() == Some(srg_id.as_str()))
+                .map(|r| SrgRule {
+                    catalog_id: entry.id.clone(),
+                    stig_title: entry.title.clone(),
+                    rule_id: r.id,
+                    stig_id: r.stig_id,
+                    group_id: r.group_id,
+                    title: r.title,
+                    severity: r.severity,
+                }),
+        );
+    }
+
+    Ok(Json(serde_json::json!({
+        "srgId": srg_id,
+        "ruleCount": rules.len(),
+        "rules": rules,
+    })))
+}
@@ -1,18 +1,32 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
+    response::IntoResponse,
     Json,
 };
+use serde::Deserialize;
 
+use crate::api::attachment;
+use crate::export::ckl::checklist_to_ckl;
+use crate::parser::ckl::{normalize_status, Asset, Checklist, ChecklistStig};
+use crate::parser::{map_severity, StigData};
 use crate::AppState;
 
-/// GET /api/stigs/:id
+#[derive(Debug, Deserialize)]
+pub struct StigQuery {
+    /// XCCDF Profile id, e.g. `MAC-1_Classified`.
+    pub profile: Option<String>,
+}
+
+/// GET /api/stigs/:id[?profile=MAC-1_Classified]
 ///
 /// Reads the pre-parsed JSON file for the given STIG ID and returns it as-is.
 /// The JSON on disk matches the frontend's internal STIG data model exactly.
+/// With `profile`, only the rules that profile selects are returned.
 pub async fn get_stig(
     State(state): State<AppState>,
     Path(id): Path<String>,
+    Query(params): Query<StigQuery>,
 ) -> Result<impl axum::response::IntoResponse, StatusCode> {
     // Sanitise the id — only allow alphanumeric + hyphens to prevent path traversal
     if !id.chars().all(|c| c.is_alphanumeric() || c == '-') {
@@ -30,11 +44,199 @@ pub async fn get_stig(
         }
     })?;
 
-    // Parse to Value so axum re-serialises with correct Content-Type
-    let value: serde_json::Value = serde_json::from_str(&contents).map_err(|e| {
+    let Some(profile_id) = params.profile else {
+        // Parse to Value so axum re-serialises with correct Content-Type
+        let value: serde_json::Value = serde_json::from_str(&contents).map_err(|e| {
+            tracing::error!("Failed to deserialise {}: {e:#}", path.display());
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+        return Ok(Json(value));
+    };
+
+    let mut stig: StigData = serde_json::from_str(&contents).map_err(|e| {
         tracing::error!("Failed to deserialise {}: {e:#}", path.display());
         StatusCode::INTERNAL_SERVER_ERROR
     })?;
 
+    if !select_profile(&mut stig, &profile_id) {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    let value = serde_json::to_value(&stig).map_err(|e| {
+        tracing::error!("Failed to serialise {}: {e:#}", path.display());
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
     Ok(Json(value))
 }
+
+/// Keep only the rules the profile `profile_id` selects.  Returns `false`,
+/// leaving the rules alone, when the benchmark has no such profile.
+fn select_profile(stig: &mut StigData, profile_id: &str) -> bool {
+    let Some(profile) = stig.profiles.iter().find(|p| p.id == profile_id) else {
+        return false;
+    };
+    stig.rules.retain(|rule| profile.selects(rule));
+    true
+}
+
+/// Body of POST /api/stigs/:id/ckl.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CklRequest {
+    pub asset: Asset,
+    pub rules: Vec<RuleReview>,
+}
+
+/// Review result for one rule, matched by Rule id, STIG id or Vuln id.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RuleReview {
+    pub rule_id: String,
+    /// `open`, `not_a_finding`, `not_applicable` or `not_reviewed`, or the
+    /// CKL spelling (`Open`, `NotAFinding`, …).
+    pub status: Option<String>,
+    pub finding_details: Option<String>,
+    pub comments: Option<String>,
+    /// `CAT I`–`CAT III`, or the XCCDF severity (`high`, `medium`, `low`).
+    pub severity_override: Option<String>,
+    pub severity_justification: Option<String>,
+}
+
+/// POST /api/stigs/:id/ckl
+///
+/// Builds a `.ckl` for this STIG without storing a checklist. Rules not
+/// listed in `rules` are exported as Not_Reviewed.  A `ruleId` that matches
+/// no rule, or a status or severity override that is not recognised, fails
+/// the request with 422 listing every such entry, rather than producing a
+/// checklist that silently leaves them unreviewed.
+///
+/// Example:
+///   curl -X POST http://localhost:8080/api/stigs/kubernetes/ckl \
+///        -H "Content-Type: application/json" \
+///        -d '{"asset":{"hostName":"k8s-cp01"},
+///             "rules":[{"ruleId":"CNTR-K8-000150","status":"open"}]}'
+pub async fn export_stig_ckl(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(request): Json<CklRequest>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    if !id.chars().all(|c| c.is_alphanumeric() || c == '-') {
+        return Err((StatusCode::BAD_REQUEST, "Invalid STIG id".into()));
+    }
+
+    let path = state.config.data_dir.join("stigs").join(format!("{id}.json"));
+    let contents = tokio::fs::read_to_string(&path).await.map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            (StatusCode::NOT_FOUND, format!("STIG '{id}' not found"))
+        } else {
+            tracing::error!("Failed to read {}: {e:#}", path.display());
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to read STIG '{id}'"))
+        }
+    })?;
+    let mut stig: StigData = serde_json::from_str(&contents).map_err(|e| {
+        tracing::error!("Failed to deserialise {}: {e:#}", path.display());
+        (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to read STIG '{id}'"))
+    })?;
+
+    let mut unmatched: Vec<String> = Vec::new();
+    let mut invalid: Vec<String> = Vec::new();
+    for review in request.rules {
+        let Some(rule) = stig.rules.iter_mut().find(|r| {
+            r.id == review.rule_id || r.stig_id == review.rule_id || r.group_id == review.rule_id
+        }) else {
+            unmatched.push(review.rule_id);
+            continue;
+        };
+        if let Some(status) = review.status {
+            match normalize_status(&status) {
+                Some(status) => rule.status = status.to_string(),
+                None => invalid.push(format!("{}: status '{status}'", review.rule_id)),
+            }
+        }
+        if let Some(details) = review.finding_details {
+            rule.finding_details = details;
+        }
+        if let Some(comments) = review.comments {
+            rule.comments = comments;
+        }
+        // Accept either the XCCDF severity (`low`) or the CAT label (`CAT III`)
+        if let Some(severity) = review.severity_override {
+            match severity.to_lowercase().as_str() {
+                "cat i" | "cat ii" | "cat iii" => {
+                    rule.severity_override = Some(severity.to_uppercase())
+                }
+                "high" | "medium" | "low" => {
+                    rule.severity_override = Some(map_severity(&severity).to_string())
+                }
+                _ => invalid.push(format!("{}: severity override '{severity}'", review.rule_id)),
+            }
+        }
+        rule.severity_justification =
+            review.severity_justification.or(rule.severity_justification.take());
+    }
+
+    if !unmatched.is_empty() || !invalid.is_empty() {
+        let mut problems = Vec::new();
+        if !unmatched.is_empty() {
+            problems.push(format!("no rule in '{id}' matches ruleId {}", unmatched.join(", ")));
+        }
+        if !invalid.is_empty() {
+            problems.push(format!("unrecognised values: {}", invalid.join(", ")));
+        }
+        return Err((StatusCode::UNPROCESSABLE_ENTITY, problems.join("; ")));
+    }
+
+    let name = if request.asset.host_name.is_empty() { id.clone() } else { request.asset.host_name.clone() };
+    let checklist = Checklist {
+        asset: request.asset,
+        stigs: vec![ChecklistStig {
+            catalog_id: Some(id),
+            stig,
+            ..Default::default()
+        }],
+        review: None,
+    };
+    Ok(attachment(&name, "ckl", "application/xml", checklist_to_ckl(&checklist).into_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{Profile, Rule};
+
+    fn rule(id: &str, group_id: &str) -> Rule {
+        Rule { id: id.into(), group_id: group_id.into(), ..Default::default() }
+    }
+
+    fn stig() -> StigData {
+        StigData {
+            profiles: vec![Profile {
+                id: "MAC-1_Classified".into(),
+                title: "I - Mission Critical Classified".into(),
+                description: String::new(),
+                selected: vec!["V-1".into(), "SV-3r1_rule".into()],
+            }],
+            rules: vec![
+                rule("SV-1r1_rule", "V-1"),
+                rule("SV-2r1_rule", "V-2"),
+                rule("SV-3r1_rule", "V-3"),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn profile_keeps_selected_rules() {
+        let mut stig = stig();
+        assert!(select_profile(&mut stig, "MAC-1_Classified"));
+        let ids: Vec<&str> = stig.rules.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, ["SV-1r1_rule", "SV-3r1_rule"]);
+    }
+
+    #[test]
+    fn unknown_profile_is_rejected() {
+        let mut stig = stig();
+        assert!(!select_profile(&mut stig, "MAC-2_Sensitive"));
+        assert_eq!(stig.rules.len(), 3);
+    }
+}
@@ -4,10 +4,15 @@ use axum::{
     Json,
 };
 use chrono::Utc;
+use std::sync::PoisonError;
 
 use crate::{
-    db::{upsert_catalog, CatalogEntry},
-    parser::{extract_all_from_library, extract_xccdf_from_zip, parse_xccdf},
+    parser::{
+        archive::ZipLimitError, extract_all_from_library, extract_xccdf_from_zip, package_ids,
+        warnings::summarize, LibraryEntry, StigData,
+    },
+    store::{fold_split_package, keep_package, library_path, save_benchmark},
+    taxonomy::CategorySource,
     AppState,
 };
 
@@ -16,7 +21,21 @@ use crate::{
 /// Accepts a multipart form upload with the following fields (order-independent):
 ///   file     — the DISA STIG ZIP (required)
 ///   id       — machine-readable slug, e.g. "windows-11" (required)
-///   category — one of Windows / Linux / Browser / Network (required)
+///   category — catalog category, e.g. "Windows" (optional; assigned from
+///              taxonomy.toml by the benchmark title when omitted, and kept
+///              by POST /api/catalog/recategorize when given)
+///
+/// Packages holding several benchmarks import each one under `id-<suffix>`
+/// (see `parser::benchmark_ids`); the response lists them all in `benchmarks`.
+/// A SCAP benchmark ZIP (`*_Benchmark.zip`) records its version and release on
+/// the catalog entry for `id`.  Tags always come from taxonomy.toml.  Parser
+/// `warnings` (see `parser::warnings`) are returned and stored on the entry.
+///
+/// Other entries of the package (READMEs, overview PDFs, …) are listed in
+/// `skipped` with the reason.  A package that breaks the ZIP limits
+/// (`ZIP_MAX_*`, see config.rs) is rejected with 413, or 422 for an entry
+/// with an unsafe path.  An imported package is kept under `DATA_DIR/packages`
+/// (see `store::keep_package`).
 ///
 /// Example:
 ///   curl -X POST http://localhost:8080/api/upload \
@@ -64,65 +83,122 @@ pub async fn upload_stig(
 
     let zip_bytes = zip_bytes.ok_or((StatusCode::BAD_REQUEST, "Missing 'file' field".into()))?;
     let id = id.ok_or((StatusCode::BAD_REQUEST, "Missing 'id' field".into()))?;
-    let category = category.ok_or((StatusCode::BAD_REQUEST, "Missing 'category' field".into()))?;
+    let category = category.filter(|c| !c.trim().is_empty());
 
-    // Extract and parse XCCDF
-    let xccdf = extract_xccdf_from_zip(&zip_bytes).map_err(|e| {
-        (StatusCode::UNPROCESSABLE_ENTITY, format!("ZIP extraction failed: {e}"))
+    // Extract every XCCDF in the package and parse each one
+    // Limit violations are reported by kind, e.g. "ZIP rejected
+    // (compression_ratio_exceeded): …"
+    let scan = extract_xccdf_from_zip(&zip_bytes, state.config.zip_limits).map_err(|e| {
+        match e.downcast_ref::<ZipLimitError>() {
+            Some(limit) => {
+                let status = match limit {
+                    ZipLimitError::UnsafePath { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+                    _ => StatusCode::PAYLOAD_TOO_LARGE,
+                };
+                (status, format!("ZIP rejected ({}): {limit}", limit.kind()))
+            }
+            None => (StatusCode::UNPROCESSABLE_ENTITY, format!("ZIP extraction failed: {e}")),
+        }
     })?;
 
-    let stig = parse_xccdf(&xccdf).map_err(|e| {
-        (StatusCode::UNPROCESSABLE_ENTITY, format!("XCCDF parse failed: {e}"))
-    })?;
+    let mut parsed = Vec::with_capacity(scan.files.len());
+    for (bench_id, file) in package_ids(&id, scan.files) {
+        let stig = file.stig.map_err(|e| {
+            (StatusCode::UNPROCESSABLE_ENTITY, format!("XCCDF parse failed ({}): {e}", file.name))
+        })?;
+        parsed.push((bench_id, file.scap, stig));
+    }
 
-    // Write JSON file
     let stigs_dir = state.config.data_dir.join("stigs");
     tokio::fs::create_dir_all(&stigs_dir).await.map_err(|e| {
         (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to create data dir: {e}"))
     })?;
 
-    let json_path = stigs_dir.join(format!("{id}.json"));
-    let json_str = serde_json::to_string(&stig).map_err(|e| {
-        (StatusCode::INTERNAL_SERVER_ERROR, format!("Serialisation failed: {e}"))
-    })?;
-    tokio::fs::write(&json_path, &json_str).await.map_err(|e| {
-        (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to write JSON: {e}"))
-    })?;
+    // Write JSON files and upsert catalog rows
+    let mut benchmarks: Vec<serde_json::Value> = Vec::with_capacity(parsed.len());
+    let mut categories: Vec<String> = Vec::with_capacity(parsed.len());
+    for (bench_id, scap, stig) in &parsed {
+        let mut classification = state
+            .taxonomy
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .classify(&stig.title);
+        if let Some(category) = &category {
+            classification = classification.pin(category.clone(), CategorySource::Upload);
+        }
+        save_benchmark(&state.pool, &stigs_dir, bench_id, &classification, stig, *scap)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("{e:#}")))?;
 
-    // Upsert catalog row
-    let rule_count = stig.rules.len() as i32;
-    let title = if stig.title.is_empty() { id.clone() } else { stig.title.clone() };
-    let entry = CatalogEntry {
-        id: id.clone(),
-        title: title.clone(),
-        category: category.clone(),
-        version: stig.version.clone(),
-        release_info: stig.release_info.clone(),
-        rule_count,
-        json_path: json_path.to_string_lossy().into_owned(),
-        last_updated: Utc::now(),
-    };
-    upsert_catalog(&state.pool, &entry).await.map_err(|e| {
-        (StatusCode::INTERNAL_SERVER_ERROR, format!("Database upsert failed: {e}"))
-    })?;
+        let rule_count = stig.rules.len();
+        tracing::info!(
+            "Uploaded {} '{bench_id}' ({}): {rule_count} rules",
+            if *scap { "SCAP benchmark" } else { "STIG" },
+            stig.title
+        );
+        if !stig.warnings.is_empty() {
+            tracing::warn!("'{bench_id}' parsed with warnings: {}", summarize(&stig.warnings));
+        }
+
+        benchmarks.push(serde_json::json!({
+            "id": bench_id,
+            "title": stig.title,
+            "version": stig.version,
+            "category": classification.category,
+            "categorySource": classification.source,
+            "tags": classification.tags,
+            "ruleCount": rule_count,
+            "scap": scap,
+            "warnings": stig.warnings,
+        }));
+        categories.push(classification.category);
+    }
+
+    // A package that used to import as one benchmark under `id` now splits
+    let saved: Vec<(&str, &StigData)> =
+        parsed.iter().map(|(bench_id, _, stig)| (bench_id.as_str(), stig)).collect();
+    fold_split_package(&state.pool, &stigs_dir, &id, &saved)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("{e:#}")))?;
 
-    tracing::info!("Uploaded STIG '{id}' ({title}): {rule_count} rules");
+    // Kept so that a later release can parse it again
+    let scap_only = parsed.iter().all(|(_, scap, _)| *scap);
+    keep_package(&state.config.data_dir, &id, scap_only, &zip_bytes)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("{e:#}")))?;
 
+    // Top-level fields describe the first benchmark; `benchmarks` lists them all
+    let first = benchmarks[0].clone();
     Ok(Json(serde_json::json!({
-        "id": id,
-        "title": title,
-        "category": category,
-        "version": stig.version,
-        "ruleCount": rule_count,
+        "id": first["id"],
+        "title": first["title"],
+        "category": categories[0],
+        "tags": first["tags"],
+        "version": first["version"],
+        "ruleCount": first["ruleCount"],
+        "warnings": first["warnings"],
+        "benchmarks": benchmarks,
+        "skipped": scan.skipped,
     })))
 }
 
 /// POST /api/upload/library
 ///
 /// Accepts a DISA SRG/STIG Library bundle ZIP (the big all-in-one download).
-/// Iterates every `*_STIG.zip` inside, parses each XCCDF, auto-assigns an ID
-/// from the inner filename and a category from the XCCDF title, then writes
-/// JSON files and upserts all catalog rows in one pass.
+/// Walks every nested ZIP inside (see `parser::archive::walk_archive`), parses
+/// each STIG and SCAP benchmark XCCDF, auto-assigns an ID from its package's
+/// filename and a category and tags from the XCCDF title (see taxonomy.toml),
+/// then writes JSON files and upserts all catalog rows in one pass.
+///
+/// The bundle is spooled to `DATA_DIR/uploads` rather than held in memory.
+/// Once it has imported anything it is kept as `DATA_DIR/library.zip`, which
+/// `store::reimport_stale_benchmarks` parses again after a parser change.
+/// Entries that fail are listed in `errorDetail` with a `kind`, so ZIP limit
+/// violations such as `compression_ratio_exceeded` stand apart from parse
+/// failures.  Everything
+/// else in the bundle (SRGs, overview PDFs, …) is listed in `skipped` with
+/// its `kind` and the `reason`.  Each benchmark carries its parser `warnings`;
+/// `warningCount` totals them across the bundle.
 ///
 /// Body limit: 500 MB (set on the route in main.rs).
 ///
@@ -133,27 +209,55 @@ pub async fn upload_library(
     State(state): State<AppState>,
     mut multipart: Multipart,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
-    // Read the single 'file' field
-    let mut zip_bytes: Option<Vec<u8>> = None;
-    while let Some(field) = multipart.next_field().await.map_err(|e| {
-        (StatusCode::BAD_REQUEST, format!("Multipart error: {e}"))
-    })? {
-        if field.name() == Some("file") {
-            let bytes = field.bytes().await.map_err(|e| {
-                (StatusCode::BAD_REQUEST, format!("Failed to read file field: {e}"))
-            })?;
-            zip_bytes = Some(bytes.to_vec());
+    let uploads_dir = state.config.data_dir.join("uploads");
+    tokio::fs::create_dir_all(&uploads_dir).await.map_err(|e| {
+        (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to create upload dir: {e}"))
+    })?;
+    let spool_path = uploads_dir.join(format!(
+        "library-{}.zip",
+        Utc::now().timestamp_nanos_opt().unwrap_or_default()
+    ));
+
+    // Stream the single 'file' field to disk
+    let spooled = spool_library(&mut multipart, &spool_path).await;
+    let size = match spooled {
+        Ok(size) => size,
+        Err(err) => {
+            let _ = tokio::fs::remove_file(&spool_path).await;
+            return Err(err);
         }
-    }
-    let zip_bytes = zip_bytes.ok_or((StatusCode::BAD_REQUEST, "Missing 'file' field".into()))?;
+    };
 
-    tracing::info!("Library bundle received ({} MB), processing…", zip_bytes.len() / 1_048_576);
+    tracing::info!("Library bundle received ({} MB), processing…", size / 1_048_576);
 
     // Parsing is CPU-bound — run on blocking thread pool
-    let (lib_entries, parse_errors) =
-        tokio::task::spawn_blocking(move || extract_all_from_library(&zip_bytes))
-            .await
-            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Task panic: {e}")))?;
+    let path = spool_path.clone();
+    let limits = state.config.zip_limits;
+    let extracted = tokio::task::spawn_blocking(move || {
+        std::fs::File::open(&path)
+            .map(|f| extract_all_from_library(std::io::BufReader::new(f), limits))
+    })
+    .await;
+
+    let scan = extracted
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Task panic: {e}")))
+        .and_then(|r| {
+            r.map_err(|e| {
+                (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to reopen upload: {e}"))
+            })
+        });
+    // The last bundle that imported anything is kept so that a later release
+    // can parse it again
+    let kept = match &scan {
+        Ok(scan) if !scan.entries.is_empty() => {
+            tokio::fs::rename(&spool_path, library_path(&state.config.data_dir)).await.is_ok()
+        }
+        _ => false,
+    };
+    if !kept {
+        let _ = tokio::fs::remove_file(&spool_path).await;
+    }
+    let scan = scan?;
 
     // Write JSON files and upsert catalog rows
     let stigs_dir = state.config.data_dir.join("stigs");
@@ -162,64 +266,140 @@ pub async fn upload_library(
     })?;
 
     let mut imported = 0usize;
+    let mut benchmarks: Vec<serde_json::Value> = Vec::new();
     let mut db_errors: Vec<serde_json::Value> = Vec::new();
+    let mut warning_count = 0usize;
+    let mut saved_entries: Vec<&LibraryEntry> = Vec::new();
 
-    for entry in lib_entries {
-        let json_path = stigs_dir.join(format!("{}.json", entry.id));
+    for entry in &scan.entries {
+        let classification = state
+            .taxonomy
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .classify(&entry.stig.title);
+        let saved = save_benchmark(
+            &state.pool,
+            &stigs_dir,
+            &entry.id,
+            &classification,
+            &entry.stig,
+            entry.scap,
+        )
+        .await;
 
-        let json_str = match serde_json::to_string(&entry.stig) {
-            Ok(s) => s,
-            Err(e) => {
-                db_errors.push(serde_json::json!({"id": entry.id, "error": e.to_string()}));
-                continue;
-            }
-        };
-        if let Err(e) = tokio::fs::write(&json_path, &json_str).await {
-            db_errors.push(serde_json::json!({"id": entry.id, "error": e.to_string()}));
-            continue;
-        }
-
-        let rule_count = entry.stig.rules.len() as i32;
-        let title = if entry.stig.title.is_empty() {
-            entry.id.clone()
-        } else {
-            entry.stig.title.clone()
-        };
-
-        let catalog_entry = CatalogEntry {
-            id: entry.id.clone(),
-            title: title.clone(),
-            category: entry.category,
-            version: entry.stig.version,
-            release_info: entry.stig.release_info,
-            rule_count,
-            json_path: json_path.to_string_lossy().into_owned(),
-            last_updated: Utc::now(),
-        };
-
-        match upsert_catalog(&state.pool, &catalog_entry).await {
+        match saved {
             Ok(_) => {
-                tracing::info!("  Imported '{}' ({title}): {rule_count} rules", entry.id);
+                let rule_count = entry.stig.rules.len();
+                tracing::info!(
+                    "  Imported {} '{}' ({}): {rule_count} rules",
+                    if entry.scap { "SCAP benchmark" } else { "STIG" },
+                    entry.id,
+                    entry.stig.title
+                );
+                if !entry.stig.warnings.is_empty() {
+                    tracing::warn!(
+                        "  '{}' parsed with warnings: {}",
+                        entry.id,
+                        summarize(&entry.stig.warnings)
+                    );
+                }
                 imported += 1;
+                saved_entries.push(entry);
+                warning_count += entry.stig.warnings.len();
+                benchmarks.push(serde_json::json!({
+                    "id": entry.id,
+                    "path": entry.path,
+                    "title": entry.stig.title,
+                    "category": classification.category,
+                    "tags": classification.tags,
+                    "ruleCount": rule_count,
+                    "scap": entry.scap,
+                    "warnings": entry.stig.warnings,
+                }));
             }
             Err(e) => {
-                db_errors.push(serde_json::json!({"id": entry.id, "error": e.to_string()}));
+                db_errors.push(serde_json::json!({
+                    "id": entry.id,
+                    "path": entry.path,
+                    "kind": "store_failed",
+                    "error": format!("{e:#}"),
+                }));
             }
         }
     }
 
-    let mut all_errors: Vec<serde_json::Value> = parse_errors
+    // Packages that used to import as one benchmark under their own ID now split
+    let mut packages: Vec<&str> = saved_entries.iter().map(|e| e.package_id.as_str()).collect();
+    packages.dedup();
+    for package_id in packages {
+        let saved: Vec<(&str, &StigData)> = saved_entries
+            .iter()
+            .filter(|e| e.package_id == package_id)
+            .map(|e| (e.id.as_str(), &e.stig))
+            .collect();
+        if let Err(e) = fold_split_package(&state.pool, &stigs_dir, package_id, &saved).await {
+            db_errors.push(serde_json::json!({
+                "id": package_id,
+                "path": "",
+                "kind": "store_failed",
+                "error": format!("{e:#}"),
+            }));
+        }
+    }
+
+    let mut all_errors: Vec<serde_json::Value> = scan
+        .errors
         .iter()
-        .map(|(id, e)| serde_json::json!({"id": id, "error": e}))
+        .map(|e| serde_json::json!({"id": e.id, "path": e.path, "kind": e.kind, "error": e.message}))
         .collect();
     all_errors.extend(db_errors);
 
     let total_errors = all_errors.len();
-    tracing::info!("Library import complete: {imported} imported, {total_errors} errors");
+    tracing::info!(
+        "Library import complete: {imported} imported, {total_errors} errors, {} skipped",
+        scan.skipped.len()
+    );
 
     Ok(Json(serde_json::json!({
         "imported": imported,
+        "benchmarks": benchmarks,
         "errors": total_errors,
         "errorDetail": all_errors,
+        "skipped": scan.skipped,
+        "warningCount": warning_count,
     })))
 }
+
+/// Copy the multipart `file` field to `path` chunk by chunk, returning its size.
+async fn spool_library(
+    multipart: &mut Multipart,
+    path: &std::path::Path,
+) -> Result<usize, (StatusCode, String)> {
+    use tokio::io::AsyncWriteExt;
+
+    let mut size: Option<usize> = None;
+    while let Some(mut field) = multipart.next_field().await.map_err(|e| {
+        (StatusCode::BAD_REQUEST, format!("Multipart error: {e}"))
+    })? {
+        if field.name() != Some("file") {
+            continue;
+        }
+        let mut out = tokio::fs::File::create(path).await.map_err(|e| {
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to create spool file: {e}"))
+        })?;
+        let mut written = 0usize;
+        while let Some(chunk) = field.chunk().await.map_err(|e| {
+            (StatusCode::BAD_REQUEST, format!("Failed to read file field: {e}"))
+        })? {
+            out.write_all(&chunk).await.map_err(|e| {
+                (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to write spool file: {e}"))
+            })?;
+            written += chunk.len();
+        }
+        out.flush().await.map_err(|e| {
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to write spool file: {e}"))
+        })?;
+        size = Some(written);
+    }
+    size.ok_or((StatusCode::BAD_REQUEST, "Missing 'file' field".into()))
+}
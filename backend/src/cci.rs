@@ -0,0 +1,454 @@
+//! CCI → NIST SP 800-53 control resolution.
+//!
+//! Controls come from the DISA CCI list (`U_CCI_List.xml`, published on
+//! cyber.mil), using each CCI's latest NIST SP 800-53 reference.  Without it,
+//! the table ported from `src/constants/cciMap.js` is used, which covers only
+//! the CCIs the browser knows about.
+
+use anyhow::{bail, Context, Result};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::{collections::HashMap, io::BufRead, path::Path};
+
+use crate::parser::{attr_value, encoding::utf8_xml};
+
+/// `(CCI, control, control title)`, sorted by CCI.  Keep in sync with
+/// `src/constants/cciMap.js`.
+const CCI_MAP: &[(&str, &str, &str)] = &[
+    ("CCI-000002", "AC-1(a)(1)", "Policy and Procedures"),
+    ("CCI-000003", "AC-1(a)(2)", "Policy and Procedures"),
+    ("CCI-000004", "AC-1(b)(1)", "Policy and Procedures"),
+    ("CCI-000007", "AC-1(b)(2)", "Policy and Procedures"),
+    ("CCI-000008", "AC-2(a)",    "Account Management"),
+    ("CCI-000009", "AC-2(b)",    "Account Management"),
+    ("CCI-000010", "AC-2(c)",    "Account Management"),
+    ("CCI-000011", "AC-2(d)",    "Account Management"),
+    ("CCI-000012", "AC-2(e)",    "Account Management"),
+    ("CCI-000013", "AC-2(f)",    "Account Management"),
+    ("CCI-000014", "AC-2(g)",    "Account Management"),
+    ("CCI-000015", "AC-2(c)",    "Account Management"),
+    ("CCI-000016", "AC-2(d)",    "Account Management"),
+    ("CCI-000017", "AC-2(e)",    "Account Management"),
+    ("CCI-000018", "AC-2(j)",    "Account Management"),
+    ("CCI-000040", "AC-3",       "Access Enforcement"),
+    ("CCI-000044", "AC-7(a)",    "Unsuccessful Logon Attempts"),
+    ("CCI-000045", "AC-7(b)",    "Unsuccessful Logon Attempts"),
+    ("CCI-000046", "AC-7(c)",    "Unsuccessful Logon Attempts"),
+    ("CCI-000054", "AC-10",      "Concurrent Session Control"),
+    ("CCI-000057", "AC-11(a)",   "Device Lock"),
+    ("CCI-000058", "AC-11(b)",   "Device Lock"),
+    ("CCI-000060", "AC-11(1)",   "Device Lock | Pattern-Hiding Displays"),
+    ("CCI-000061", "AC-12",      "Session Termination"),
+    ("CCI-000067", "AC-17(2)",   "Remote Access | Protection of Confidentiality/Integrity"),
+    ("CCI-000068", "IA-7",       "Cryptographic Module Authentication"),
+    ("CCI-000130", "AU-3(a)",    "Content of Audit Records"),
+    ("CCI-000131", "AU-3(b)",    "Content of Audit Records"),
+    ("CCI-000132", "AU-3(c)",    "Content of Audit Records"),
+    ("CCI-000133", "AU-3(d)",    "Content of Audit Records"),
+    ("CCI-000134", "AU-3(e)",    "Content of Audit Records"),
+    ("CCI-000135", "AU-3(f)",    "Content of Audit Records"),
+    ("CCI-000139", "AU-5(a)",    "Response to Audit Logging Process Failures"),
+    ("CCI-000140", "AU-5(b)",    "Response to Audit Logging Process Failures"),
+    ("CCI-000154", "AU-7(1)",    "Audit Record Review, Analysis, and Reporting | Automatic Processing"),
+    ("CCI-000158", "AU-9",       "Protection of Audit Information"),
+    ("CCI-000159", "AU-9(a)",    "Protection of Audit Information"),
+    ("CCI-000163", "AU-9(3)",    "Protection of Audit Information | Cryptographic Protection"),
+    ("CCI-000169", "AU-12(a)",   "Audit Record Generation"),
+    ("CCI-000170", "AU-12(b)",   "Audit Record Generation"),
+    ("CCI-000171", "AU-12(c)",   "Audit Record Generation"),
+    ("CCI-000172", "AU-12(d)",   "Audit Record Generation"),
+    ("CCI-000185", "CM-1(a)(1)", "Configuration Management Policy and Procedures"),
+    ("CCI-000192", "IA-5(1)(a)", "Authenticator Management | Password-Based Authentication (Uppercase)"),
+    ("CCI-000193", "IA-5(1)(a)", "Authenticator Management | Password-Based Authentication (Lowercase)"),
+    ("CCI-000194", "IA-5(1)(a)", "Authenticator Management | Password-Based Authentication (Numeric)"),
+    ("CCI-000195", "IA-5(1)(b)", "Authenticator Management | Password-Based Authentication (Min Lifetime)"),
+    ("CCI-000196", "IA-5(1)(c)", "Authenticator Management | Password-Based Authentication (Max Lifetime)"),
+    ("CCI-000197", "IA-5(1)(d)", "Authenticator Management | Password-Based Authentication (Reuse)"),
+    ("CCI-000198", "IA-5(1)(e)", "Authenticator Management | Password-Based Authentication (Encryption)"),
+    ("CCI-000199", "IA-5(1)(f)", "Authenticator Management | Password-Based Authentication (Min Length)"),
+    ("CCI-000200", "IA-5(1)(g)", "Authenticator Management | Password-Based Authentication (Complexity)"),
+    ("CCI-000205", "IA-5(2)(a)", "Authenticator Management | PKI-Based Authentication"),
+    ("CCI-000213", "AC-3",       "Access Enforcement"),
+    ("CCI-000214", "AC-3(1)",    "Access Enforcement | Restricted Access to Privileged Functions"),
+    ("CCI-000215", "AC-3(2)",    "Access Enforcement | Dual Authorization"),
+    ("CCI-000225", "AC-6",       "Least Privilege"),
+    ("CCI-000226", "AC-6(1)",    "Least Privilege | Authorize Access to Security Functions"),
+    ("CCI-000230", "AC-6(5)",    "Least Privilege | Privileged Accounts"),
+    ("CCI-000238", "AC-6(9)",    "Least Privilege | Log Use of Privileged Functions"),
+    ("CCI-000239", "AC-6(10)",   "Least Privilege | Prohibit Non-Privileged Users"),
+    ("CCI-000258", "AC-17",      "Remote Access"),
+    ("CCI-000295", "CM-3",       "Configuration Change Control"),
+    ("CCI-000296", "CM-3(a)",    "Configuration Change Control"),
+    ("CCI-000318", "CM-4",       "Security and Privacy Impact Analysis"),
+    ("CCI-000345", "CM-5",       "Access Restrictions for Change"),
+    ("CCI-000366", "CM-6(a)",    "Configuration Settings"),
+    ("CCI-000370", "CM-6(b)",    "Configuration Settings"),
+    ("CCI-000381", "CM-7(a)",    "Least Functionality"),
+    ("CCI-000382", "CM-7(b)",    "Least Functionality"),
+    ("CCI-000764", "IA-2",       "Identification and Authentication (Organizational Users)"),
+    ("CCI-000765", "IA-2(1)",    "Identification and Authentication | Multi-Factor Authentication to Privileged Accounts"),
+    ("CCI-000766", "IA-2(2)",    "Identification and Authentication | Multi-Factor Authentication to Non-Privileged Accounts"),
+    ("CCI-000767", "IA-2(3)",    "Identification and Authentication | Local Access to Privileged Accounts"),
+    ("CCI-000768", "IA-2(4)",    "Identification and Authentication | Local Access to Non-Privileged Accounts"),
+    ("CCI-000778", "IA-3",       "Device Identification and Authentication"),
+    ("CCI-000795", "IA-4(e)",    "Identifier Management"),
+    ("CCI-000803", "IA-6",       "Authentication Feedback"),
+    ("CCI-000804", "IA-7",       "Cryptographic Module Authentication"),
+    ("CCI-000879", "MA-2(a)",    "Controlled Maintenance"),
+    ("CCI-000946", "PE-2(a)",    "Physical Access Authorizations"),
+    ("CCI-001082", "SC-2",       "Separation of System and User Functionality"),
+    ("CCI-001084", "SC-3",       "Security Function Isolation"),
+    ("CCI-001090", "SC-4",       "Information in Shared System Resources"),
+    ("CCI-001095", "SC-5",       "Denial of Service Protection"),
+    ("CCI-001133", "SC-10",      "Network Disconnect"),
+    ("CCI-001135", "SC-10",      "Network Disconnect"),
+    ("CCI-001167", "SC-18(1)",   "Mobile Code | Identify Unacceptable Code / Take Corrective Actions"),
+    ("CCI-001170", "SI-3",       "Malicious Code Protection"),
+    ("CCI-001184", "SC-23",      "Session Authenticity"),
+    ("CCI-001185", "SC-23(1)",   "Session Authenticity | Invalidate Session Identifiers at Logout"),
+    ("CCI-001186", "SC-23(3)",   "Session Authenticity | Unique Session Identifiers with Randomization"),
+    ("CCI-001187", "SC-23(5)",   "Session Authenticity | Allowed Certificate Authorities"),
+    ("CCI-001188", "AC-2(5)",    "Account Management | Inactivity Logout"),
+    ("CCI-001199", "SC-28",      "Protection of Information at Rest"),
+    ("CCI-001200", "SC-28(1)",   "Protection of Information at Rest | Cryptographic Protection"),
+    ("CCI-001312", "SI-11(a)",   "Error Handling"),
+    ("CCI-001313", "SI-11(b)",   "Error Handling"),
+    ("CCI-001314", "SI-11",      "Error Handling"),
+    ("CCI-001368", "AC-4",       "Information Flow Enforcement"),
+    ("CCI-001384", "AC-8(a)",    "System Use Notification"),
+    ("CCI-001385", "AC-8(b)",    "System Use Notification"),
+    ("CCI-001386", "AC-8(c)(1)", "System Use Notification"),
+    ("CCI-001387", "AC-8(c)(2)", "System Use Notification"),
+    ("CCI-001388", "AC-8(c)(3)", "System Use Notification"),
+    ("CCI-001389", "SC-12",      "Cryptographic Key Establishment and Management"),
+    ("CCI-001390", "SC-12(1)",   "Cryptographic Key Establishment and Management | Availability"),
+    ("CCI-001391", "SC-12(2)",   "Cryptographic Key Establishment and Management | Symmetric Keys"),
+    ("CCI-001392", "SC-12(3)",   "Cryptographic Key Establishment and Management | Asymmetric Keys"),
+    ("CCI-001402", "SC-13",      "Cryptographic Protection"),
+    ("CCI-001403", "AC-2(4)",    "Account Management | Automated Audit Actions"),
+    ("CCI-001404", "AC-2(4)",    "Account Management | Automated Audit Actions"),
+    ("CCI-001405", "AC-2(4)",    "Account Management | Automated Audit Actions"),
+    ("CCI-001406", "AC-2(4)",    "Account Management | Automated Audit Actions"),
+    ("CCI-001414", "AC-17(1)",   "Remote Access | Monitoring and Control"),
+    ("CCI-001499", "CM-11",      "User-Installed Software"),
+    ("CCI-001544", "AC-6(8)",    "Least Privilege | Security Functions"),
+    ("CCI-001546", "AC-2(1)",    "Account Management | Automated System Account Management"),
+    ("CCI-001547", "AC-2(2)",    "Account Management | Automated Temporary and Emergency Account Management"),
+    ("CCI-001619", "IA-5(1)(h)", "Authenticator Management | Password-Based Authentication (Special Characters)"),
+    ("CCI-001620", "IA-5(1)(i)", "Authenticator Management | Password-Based Authentication (Prohibit Reuse)"),
+    ("CCI-001621", "AC-12(1)",   "Session Termination | User-Initiated Logouts / Message Displays"),
+    ("CCI-001625", "SA-10",      "Developer Configuration Management"),
+    ("CCI-001632", "SC-8",       "Transmission Confidentiality and Integrity"),
+    ("CCI-001633", "SC-8(1)",    "Transmission Confidentiality and Integrity | Cryptographic Protection"),
+    ("CCI-001665", "SC-39",      "Process Isolation"),
+    ("CCI-001749", "CM-11(2)",   "User-Installed Software | Software Installation with Privileged Status"),
+    ("CCI-001752", "CM-8(3)(a)", "System Component Inventory | Automated Unauthorized Component Detection"),
+    ("CCI-001753", "CM-8(3)(b)", "System Component Inventory | Automated Unauthorized Component Detection"),
+    ("CCI-001754", "CM-8(5)",    "System Component Inventory | No Duplicate Accounting of Components"),
+    ("CCI-001755", "IA-3(1)",    "Device Identification and Authentication | Cryptographic Bidirectional Authentication"),
+    ("CCI-001762", "CM-7(5)(b)", "Least Functionality | Authorized Software / Allow-by-Exception"),
+    ("CCI-001812", "AU-11",      "Audit Record Retention"),
+    ("CCI-001813", "CM-6(1)",    "Configuration Settings | Automated Management / Application / Verification"),
+    ("CCI-001814", "CM-6(1)",    "Configuration Settings | Automated Management / Application / Verification"),
+    ("CCI-001851", "AU-4(1)",    "Audit Log Storage Capacity | Transfer to Alternate Storage"),
+    ("CCI-001852", "AU-11",      "Audit Record Retention"),
+    ("CCI-001853", "AU-12(1)",   "Audit Record Generation | System-Wide and Time-Correlated Audit Trail"),
+    ("CCI-001854", "AU-12(3)",   "Audit Record Generation | Changes by Authorized Individuals"),
+    ("CCI-001855", "AU-13",      "Monitoring for Information Disclosure"),
+    ("CCI-001862", "SI-2(6)",    "Flaw Remediation | Removal of Previous Versions of Software/Firmware"),
+    ("CCI-001876", "SI-7(1)",    "Software, Firmware, and Information Integrity | Integrity Checks"),
+    ("CCI-001877", "SI-7(2)",    "Software, Firmware, and Information Integrity | Automated Notifications of Integrity Violations"),
+    ("CCI-001878", "SI-7(5)",    "Software, Firmware, and Information Integrity | Automated Response to Integrity Violations"),
+    ("CCI-001879", "SI-7(7)",    "Software, Firmware, and Information Integrity | Integration of Detection and Response"),
+    ("CCI-001880", "SI-7(14)",   "Software, Firmware, and Information Integrity | Binary or Machine Executable Code"),
+    ("CCI-001891", "AU-8(1)",    "Time Stamps | Synchronization with Authoritative Time Source"),
+    ("CCI-001892", "AU-8(1)(b)", "Time Stamps | Synchronization with Authoritative Time Source"),
+    ("CCI-001893", "AU-8(2)",    "Time Stamps | Secondary Authoritative Time Source"),
+    ("CCI-001894", "AU-8(a)",    "Time Stamps"),
+    ("CCI-001895", "AU-8(b)",    "Time Stamps"),
+    ("CCI-002001", "IA-2(11)",   "Identification and Authentication | Remote Access - Separate Device"),
+    ("CCI-002002", "IA-2(12)",   "Identification and Authentication | Acceptance of PIV Credentials"),
+    ("CCI-002004", "IA-2(8)",    "Identification and Authentication | Access to Accounts - Replay Resistant"),
+    ("CCI-002007", "IA-2(6)",    "Identification and Authentication | Access to Accounts - Separate Device"),
+    ("CCI-002009", "IA-2(3)",    "Identification and Authentication | Local Access to Privileged Accounts"),
+    ("CCI-002010", "IA-8(2)",    "Identification and Authentication (Non-Organizational Users) | Acceptance of External Authenticators"),
+    ("CCI-002016", "IA-11",      "Re-Authentication"),
+    ("CCI-002038", "IA-5(13)",   "Authenticator Management | Expiration of Cached Authenticators"),
+    ("CCI-002039", "IA-5(14)",   "Authenticator Management | Managing Content of PKI Trust Stores"),
+    ("CCI-002040", "IA-5(15)",   "Authenticator Management | GSA-Approved Products and Services"),
+    ("CCI-002041", "IA-5(16)",   "Authenticator Management | In-Person or Trusted Third-Party Registration"),
+    ("CCI-002061", "AC-2(12)",   "Account Management | Account Monitoring for Atypical Usage"),
+    ("CCI-002062", "AC-2(13)",   "Account Management | Disable Accounts for High-Risk Individuals"),
+    ("CCI-002063", "AC-2(7)",    "Account Management | Privileged User Accounts"),
+    ("CCI-002100", "AC-17(4)",   "Remote Access | Privileged Commands/Access"),
+    ("CCI-002105", "AC-20",      "Use of External Systems"),
+    ("CCI-002132", "AC-2(11)",   "Account Management | Usage Conditions"),
+    ("CCI-002133", "AC-2(10)",   "Account Management | Shared and Group Account Credential Change"),
+    ("CCI-002142", "AC-3(4)",    "Access Enforcement | Discretionary Access Control"),
+    ("CCI-002149", "AC-3(8)",    "Access Enforcement | Revocation of Access Authorizations"),
+    ("CCI-002165", "AC-3(9)",    "Access Enforcement | Controlled Release"),
+    ("CCI-002176", "AC-3(13)",   "Access Enforcement | Attribute-Based Access Control"),
+    ("CCI-002184", "AC-6(7)",    "Least Privilege | Review of User Privileges"),
+    ("CCI-002192", "AC-6(2)",    "Least Privilege | Non-Privileged Access for Non-Security Functions"),
+    ("CCI-002205", "AC-14",      "Permitted Actions Without Identification or Authentication"),
+    ("CCI-002234", "AC-6(9)",    "Least Privilege | Log Use of Privileged Functions"),
+    ("CCI-002235", "AC-6(10)",   "Least Privilege | Prohibit Non-Privileged Users from Executing Privileged Functions"),
+    ("CCI-002361", "AC-12",      "Session Termination"),
+    ("CCI-002385", "SI-10",      "Information Input Validation"),
+    ("CCI-002418", "SC-8",       "Transmission Confidentiality and Integrity"),
+    ("CCI-002419", "SC-8(1)",    "Transmission Confidentiality and Integrity | Cryptographic Protection"),
+    ("CCI-002420", "SC-8(1)",    "Transmission Confidentiality and Integrity | Cryptographic Protection"),
+    ("CCI-002421", "SC-8(2)",    "Transmission Confidentiality and Integrity | Pre/Post Transmission Handling"),
+    ("CCI-002422", "SC-8(3)",    "Transmission Confidentiality and Integrity | Cryptographic Protection for Message Externals"),
+    ("CCI-002423", "SC-8(4)",    "Transmission Confidentiality and Integrity | Conceal or Randomize Communications"),
+    ("CCI-002427", "SC-9",       "Transmission Confidentiality"),
+    ("CCI-002430", "SC-13",      "Cryptographic Protection"),
+    ("CCI-002450", "SC-13(1)",   "Cryptographic Protection | FIPS-Validated Cryptography"),
+    ("CCI-002454", "SC-15",      "Collaborative Computing Devices and Applications"),
+    ("CCI-002461", "SC-17",      "Public Key Infrastructure Certificates"),
+    ("CCI-002470", "SC-20",      "Secure Name/Address Resolution Service (Authoritative Source)"),
+    ("CCI-002471", "SC-20(a)",   "Secure Name/Address Resolution Service"),
+    ("CCI-002472", "SC-20(b)",   "Secure Name/Address Resolution Service"),
+    ("CCI-002475", "SC-28(1)",   "Protection of Information at Rest | Cryptographic Protection"),
+    ("CCI-002476", "SC-28(2)",   "Protection of Information at Rest | Off-Line Storage"),
+    ("CCI-002530", "SI-2(5)",    "Flaw Remediation | Automatic Software/Firmware Updates"),
+    ("CCI-002534", "SI-3(1)",    "Malicious Code Protection | Signature Updates"),
+    ("CCI-002605", "SI-2(a)",    "Flaw Remediation"),
+    ("CCI-002607", "SI-2(c)",    "Flaw Remediation"),
+    ("CCI-002617", "SI-2(2)",    "Flaw Remediation | Automated Flaw Remediation Status"),
+    ("CCI-002696", "SI-6(a)",    "Security and Privacy Function Verification"),
+    ("CCI-002699", "SI-6(b)",    "Security and Privacy Function Verification"),
+    ("CCI-002702", "SI-6(d)",    "Security and Privacy Function Verification"),
+    ("CCI-002754", "SI-7(15)",   "Software, Firmware, and Information Integrity | Code Authentication"),
+];
+
+/// Controls by CCI, e.g. `CCI-000015` → `AC-2(c)`.
+#[derive(Debug, Clone, Default)]
+pub struct CciList {
+    controls: HashMap<String, String>,
+}
+
+impl CciList {
+    /// The table ported from the frontend.
+    pub fn builtin() -> Self {
+        let controls = CCI_MAP
+            .iter()
+            .map(|(cci, control, _)| (cci.to_string(), control.to_string()))
+            .collect();
+        Self { controls }
+    }
+
+    /// Parse a DISA CCI list.  CCIs without a NIST SP 800-53 reference are
+    /// left out.
+    pub fn from_xml<R: BufRead>(xml: R) -> Result<Self> {
+        let mut reader = Reader::from_reader(utf8_xml(xml)?);
+        let mut controls = HashMap::new();
+        let mut seen_list = false;
+        let mut item: Option<String> = None;
+        // Latest revision referenced so far by the current item, and its index
+        let mut latest: Option<(u32, String)> = None;
+
+        let mut buf = Vec::new();
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(Event::Start(ref e)) | Ok(Event::Empty(ref e)) => {
+                    match e.local_name().as_ref() {
+                        b"cci_list" => seen_list = true,
+                        b"cci_item" => {
+                            item = attr_value(e.as_ref(), "id");
+                            latest = None;
+                        }
+                        b"reference" if item.is_some() => {
+                            let title = attr_value(e.as_ref(), "title").unwrap_or_default();
+                            let index = attr_value(e.as_ref(), "index").unwrap_or_default();
+                            // SP 800-53A indexes assessment procedures, not controls
+                            let controls_ref =
+                                title.starts_with("NIST SP 800-53") && !title.contains("800-53A");
+                            if controls_ref && !index.trim().is_empty() {
+                                let version = attr_value(e.as_ref(), "version")
+                                    .and_then(|v| v.trim().parse().ok())
+                                    .unwrap_or(0);
+                                if latest.as_ref().is_none_or(|(v, _)| version > *v) {
+                                    latest = Some((version, index));
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                Ok(Event::End(ref e)) if e.local_name().as_ref() == b"cci_item" => {
+                    if let (Some(cci), Some((_, index))) = (item.take(), latest.take()) {
+                        controls.insert(cci, control_from_index(&index));
+                    }
+                }
+                Ok(Event::Eof) => break,
+                Err(e) => bail!("XML parse error: {e}"),
+                _ => {}
+            }
+            buf.clear();
+        }
+
+        if !seen_list {
+            bail!("No cci_list element found");
+        }
+        Ok(Self { controls })
+    }
+
+    pub fn len(&self) -> usize {
+        self.controls.len()
+    }
+
+    /// Control identifier of a CCI, e.g. `CCI-000015` → `AC-2(c)`.
+    pub fn control(&self, cci: &str) -> Option<&str> {
+        self.controls.get(cci).map(String::as_str)
+    }
+
+    /// Distinct eMASS control numbers for a rule's CCIs, in first-seen order,
+    /// and the CCIs that resolve to no control.
+    pub fn control_numbers(&self, cci_ids: &[String]) -> (Vec<String>, Vec<String>) {
+        let mut numbers: Vec<String> = Vec::new();
+        let mut unmapped = Vec::new();
+        for cci in cci_ids {
+            match self.control(cci) {
+                Some(control) => {
+                    let number = control_number(control);
+                    if !numbers.contains(&number) {
+                        numbers.push(number);
+                    }
+                }
+                None => unmapped.push(cci.clone()),
+            }
+        }
+        (numbers, unmapped)
+    }
+}
+
+/// Load the CCI list at `path`, falling back to the built-in table when there
+/// is no file.
+pub fn load_cci_list(path: &Path) -> Result<CciList> {
+    match std::fs::File::open(path) {
+        Ok(file) => CciList::from_xml(std::io::BufReader::new(file))
+            .with_context(|| format!("Cannot parse {}", path.display())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            tracing::warn!(
+                "{} not found; resolving CCIs with the built-in table of {} entries",
+                path.display(),
+                CCI_MAP.len()
+            );
+            Ok(CciList::builtin())
+        }
+        Err(e) => Err(e).with_context(|| format!("Cannot read {}", path.display())),
+    }
+}
+
+/// Control identifier from a CCI list reference index, e.g. `AC-1 a 1 (a)` →
+/// `AC-1(a)(1)(a)`, `IA-5 (1) (c)` → `IA-5(1)(c)`.
+fn control_from_index(index: &str) -> String {
+    let mut parts = index.split_whitespace();
+    let mut control = parts.next().unwrap_or_default().to_string();
+    for part in parts {
+        if part.starts_with('(') {
+            control.push_str(part);
+        } else {
+            control.push_str(&format!("({part})"));
+        }
+    }
+    control
+}
+
+/// Control number as eMASS expects it — the control or enhancement without
+/// its lettered parts, e.g. `AC-1(a)(1)` → `AC-1`, `IA-5(1)(c)` → `IA-5(1)`.
+fn control_number(control: &str) -> String {
+    let base_end = control.find('(').unwrap_or(control.len());
+    let mut number = control[..base_end].to_string();
+    // The enhancement is the numbered part right after the base control
+    if let Some(part) = control[base_end..].split_inclusive(')').next() {
+        let inner = part.trim_start_matches('(').trim_end_matches(')');
+        if !inner.is_empty() && inner.chars().all(|c| c.is_ascii_digit()) {
+            number.push_str(part);
+        }
+    }
+    number
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CCI_LIST: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<cci_list xmlns="http://iase.disa.mil/cci">
+  <metadata><version>2024-01-01</version></metadata>
+  <cci_items>
+    <cci_item id="CCI-000001">
+      <status>draft</status>
+      <definition>Develop an access control policy.</definition>
+      <references>
+        <reference creator="NIST" title="NIST SP 800-53" version="3" index="AC-1 a" />
+        <reference creator="NIST" title="NIST SP 800-53 Revision 5" version="5" index="AC-1 a 1 (a)" />
+        <reference creator="NIST" title="NIST SP 800-53 Revision 4" version="4" index="AC-1 a 1" />
+        <reference creator="NIST" title="NIST SP 800-53A" version="1" index="AC-1.1 (i and ii)" />
+      </references>
+    </cci_item>
+    <cci_item id="CCI-000185">
+      <references>
+        <reference creator="NIST" title="NIST SP 800-53 Revision 5" version="5" index="IA-5 (2) (b) (1)" />
+      </references>
+    </cci_item>
+    <cci_item id="CCI-004000">
+      <references>
+        <reference creator="NIST" title="NIST SP 800-53A" version="1" index="AC-2.1" />
+      </references>
+    </cci_item>
+  </cci_items>
+</cci_list>"#;
+
+    fn ids(ccis: &[&str]) -> Vec<String> {
+        ccis.iter().map(|c| c.to_string()).collect()
+    }
+
+    #[test]
+    fn builtin_table_is_sorted_and_unique() {
+        assert!(CCI_MAP.windows(2).all(|w| w[0].0 < w[1].0));
+        assert_eq!(CciList::builtin().len(), CCI_MAP.len());
+    }
+
+    #[test]
+    fn cci_list_uses_the_latest_800_53_revision() {
+        let list = CciList::from_xml(CCI_LIST.as_bytes()).unwrap();
+        assert_eq!(list.control("CCI-000001"), Some("AC-1(a)(1)(a)"));
+        assert_eq!(list.control("CCI-000185"), Some("IA-5(2)(b)(1)"));
+        // Only an 800-53A reference: no control
+        assert_eq!(list.control("CCI-004000"), None);
+        assert_eq!(list.len(), 2);
+    }
+
+    #[test]
+    fn rejects_other_documents() {
+        assert!(CciList::from_xml(&b"<Benchmark/>"[..]).is_err());
+    }
+
+    #[test]
+    fn control_from_index_parenthesises_every_part() {
+        assert_eq!(control_from_index("AC-2 a"), "AC-2(a)");
+        assert_eq!(control_from_index("AC-17 (2)"), "AC-17(2)");
+        assert_eq!(control_from_index("AC-1 a 1 (a)"), "AC-1(a)(1)(a)");
+        assert_eq!(control_from_index("SC-13"), "SC-13");
+    }
+
+    #[test]
+    fn control_number_keeps_only_the_enhancement() {
+        assert_eq!(control_number("AC-1(a)(1)"), "AC-1");
+        assert_eq!(control_number("IA-5(1)(c)"), "IA-5(1)");
+        assert_eq!(control_number("IA-5(2)(b)(1)"), "IA-5(2)");
+        assert_eq!(control_number("SC-13"), "SC-13");
+    }
+
+    #[test]
+    fn control_numbers_reports_unmapped_ccis() {
+        let list = CciList::builtin();
+        let (numbers, unmapped) =
+            list.control_numbers(&ids(&["CCI-000015", "CCI-000016", "CCI-999999", "CCI-000068"]));
+        assert_eq!(numbers, ["AC-2", "IA-7"]);
+        assert_eq!(unmapped, ["CCI-999999"]);
+    }
+}
@@ -2,6 +2,8 @@ use anyhow::{Context, Result};
 use serde::Deserialize;
 use std::{fs, path::PathBuf};
 
+use crate::parser::archive::ZipLimits;
+
 /// One entry from stig-sources.toml — the curated DISA download manifest.
 #[derive(Debug, Clone, Deserialize)]
 pub struct StigSource {
@@ -28,6 +30,10 @@ pub struct Config {
     pub data_dir: PathBuf,
     /// How often the sync scheduler runs (hours).
     pub sync_interval_hours: u64,
+    /// Bounds on what an uploaded or downloaded ZIP may decompress to.
+    pub zip_limits: ZipLimits,
+    /// DISA CCI list used to resolve CCIs to NIST SP 800-53 controls.
+    pub cci_list: PathBuf,
 }
 
 impl Config {
@@ -47,10 +53,48 @@ impl Config {
                 .unwrap_or_else(|_| "24".into())
                 .parse()
                 .context("STIG_SYNC_INTERVAL_HOURS must be a positive integer")?,
+            zip_limits: zip_limits_from_env()?,
+            cci_list: PathBuf::from(
+                std::env::var("CCI_LIST").unwrap_or_else(|_| "U_CCI_List.xml".into()),
+            ),
         })
     }
 }
 
+/// `ZIP_MAX_TOTAL_MB`, `ZIP_MAX_ENTRY_MB`, `ZIP_MAX_ARCHIVE_MB`,
+/// `ZIP_MAX_RATIO`, `ZIP_MAX_ENTRIES` and `ZIP_MAX_DEPTH`, each falling back
+/// to `ZipLimits::default()`.
+fn zip_limits_from_env() -> Result<ZipLimits> {
+    fn var<T: std::str::FromStr>(name: &str) -> Result<Option<T>> {
+        match std::env::var(name) {
+            Ok(v) => v
+                .parse()
+                .map(Some)
+                .map_err(|_| anyhow::anyhow!("{name} must be a positive integer")),
+            Err(_) => Ok(None),
+        }
+    }
+    fn bytes(name: &str, default: u64) -> Result<u64> {
+        const MB: u64 = 1024 * 1024;
+        match var::<u64>(name)? {
+            Some(mb) => mb
+                .checked_mul(MB)
+                .ok_or_else(|| anyhow::anyhow!("{name} is too large")),
+            None => Ok(default),
+        }
+    }
+
+    let defaults = ZipLimits::default();
+    Ok(ZipLimits {
+        max_total_bytes: bytes("ZIP_MAX_TOTAL_MB", defaults.max_total_bytes)?,
+        max_entry_bytes: bytes("ZIP_MAX_ENTRY_MB", defaults.max_entry_bytes)?,
+        max_archive_bytes: bytes("ZIP_MAX_ARCHIVE_MB", defaults.max_archive_bytes)?,
+        max_ratio: var("ZIP_MAX_RATIO")?.unwrap_or(defaults.max_ratio),
+        max_entries: var("ZIP_MAX_ENTRIES")?.unwrap_or(defaults.max_entries),
+        max_depth: var("ZIP_MAX_DEPTH")?.unwrap_or(defaults.max_depth),
+    })
+}
+
 /// Load and parse `stig-sources.toml` from the current directory.
 pub fn load_sources() -> Result<Vec<StigSource>> {
     let raw = fs::read_to_string("stig-sources.toml")
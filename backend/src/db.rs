@@ -1,20 +1,115 @@
 use anyhow::Result;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::Serialize;
-use sqlx::{PgPool, postgres::PgPoolOptions};
+use sqlx::{types::Json, PgPool, Postgres, QueryBuilder, postgres::PgPoolOptions};
+use std::path::Path;
+
+use crate::parser::ckl::Checklist;
+use crate::taxonomy::{CategorySource, Classification};
+use crate::parser::{
+    benchmark_date, release_label, release_number, warnings::ParseWarning, StigData, PARSER_VERSION,
+};
 
 /// Catalog entry as stored in PostgreSQL and returned by GET /api/catalog.
 #[derive(Debug, Clone, Serialize, sqlx::FromRow)]
 #[serde(rename_all = "camelCase")]
 pub struct CatalogEntry {
     pub id: String,
+    /// XCCDF Benchmark id, e.g. `Kubernetes_STIG`.
+    pub benchmark_id: String,
     pub title: String,
     pub category: String,
+    /// `taxonomy`, or `manifest` / `upload` for a pinned category.
+    pub category_source: CategorySource,
+    /// Every category and tag the taxonomy matched, e.g. `["Network", "Firewall"]`.
+    pub tags: Vec<String>,
     pub version: String,
     pub release_info: String,
+    /// Parsed from `release_info`, e.g. 5 and 2026-01-05 for
+    /// "Release: 5 Benchmark Date: 05 Jan 2026".
+    pub release_number: Option<i32>,
+    pub benchmark_date: Option<NaiveDate>,
+    /// `V<version>R<release>`, e.g. `V2R5`.
+    pub release_label: Option<String>,
     pub rule_count: i32,
     pub json_path: String,
     pub last_updated: DateTime<Utc>,
+    /// Version of the SCAP benchmark imported for this STIG, if any.
+    /// Maintained by `set_scap_benchmark`, not by `upsert_catalog`.
+    pub scap_version: Option<String>,
+    pub scap_release_info: Option<String>,
+    /// Problems found when the SCAP benchmark was parsed.
+    pub scap_warnings: Json<Vec<ParseWarning>>,
+    /// Benchmark `<status>` and its date, e.g. `accepted` on 2025-12-10.
+    pub status: String,
+    pub status_date: Option<NaiveDate>,
+    /// `<notice>` text, or its id when DISA leaves the text empty.
+    pub notice: Option<String>,
+    pub front_matter: Option<String>,
+    pub rear_matter: Option<String>,
+    /// `dc:publisher` / `dc:source` of the Benchmark `<reference>`.
+    pub publisher: Option<String>,
+    pub source: Option<String>,
+    /// CPE names the benchmark applies to.
+    pub platforms: Vec<String>,
+    /// Distinct SRG ids of the rules (see `StigData::srg_ids`); too long to
+    /// list in the catalog, so only queried by GET /api/srg/:srg_id.
+    #[serde(skip)]
+    pub srg_ids: Vec<String>,
+    /// Problems found when the benchmark was parsed.
+    pub warnings: Json<Vec<ParseWarning>>,
+    /// `parser::PARSER_VERSION` the JSON was written with; 0 before it was
+    /// recorded.  Entries below the current version need re-importing.
+    pub parser_version: i32,
+}
+
+impl CatalogEntry {
+    /// Build the catalog row for a parsed STIG stored at `json_path`.
+    pub fn from_stig(
+        id: &str,
+        classification: &Classification,
+        stig: &StigData,
+        json_path: &Path,
+    ) -> Self {
+        Self {
+            id: id.to_string(),
+            benchmark_id: stig.benchmark_id.clone(),
+            title: if stig.title.is_empty() { id.to_string() } else { stig.title.clone() },
+            category: classification.category.clone(),
+            category_source: classification.source,
+            tags: classification.tags.clone(),
+            version: stig.version.clone(),
+            release_info: stig.release_info.clone(),
+            release_number: release_number(&stig.release_info),
+            benchmark_date: benchmark_date(&stig.release_info),
+            release_label: release_label(&stig.version, release_number(&stig.release_info)),
+            rule_count: stig.rules.len() as i32,
+            json_path: json_path.to_string_lossy().into_owned(),
+            last_updated: Utc::now(),
+            scap_version: None,
+            scap_release_info: None,
+            scap_warnings: Json(Vec::new()),
+            status: stig.status.clone(),
+            status_date: stig
+                .status_date
+                .as_deref()
+                .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok()),
+            notice: stig
+                .notices
+                .iter()
+                .map(|n| if n.text.is_empty() { n.id.as_str() } else { n.text.as_str() })
+                .find(|s| !s.is_empty())
+                .map(str::to_string),
+            front_matter: stig.front_matter.clone(),
+            rear_matter: stig.rear_matter.clone(),
+            publisher: stig.references.first().map(|r| r.publisher.clone()),
+            source: stig.references.first().map(|r| r.source.clone()),
+            platforms: stig.platforms.clone(),
+            srg_ids: stig.srg_ids(),
+            warnings: Json(stig.warnings.clone()),
+            parser_version: stig.parser_version.unwrap_or(0) as i32,
+        }
+    }
 }
 
 /// Create a connection pool and run pending migrations.
@@ -29,28 +124,82 @@ pub async fn init_pool(database_url: &str) -> Result<PgPool> {
     Ok(pool)
 }
 
-/// Return all catalog entries, optionally filtered by category.
-pub async fn list_catalog(pool: &PgPool, category: Option<&str>) -> Result<Vec<CatalogEntry>> {
-    let rows = match category {
-        Some(cat) => {
-            sqlx::query_as::<_, CatalogEntry>(
-                "SELECT * FROM stigs_catalog WHERE category = $1 ORDER BY title",
-            )
-            .bind(cat)
-            .fetch_all(pool)
-            .await?
-        }
-        None => {
-            sqlx::query_as::<_, CatalogEntry>(
-                "SELECT * FROM stigs_catalog ORDER BY category, title",
-            )
-            .fetch_all(pool)
-            .await?
+/// Sort order for `list_catalog`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CatalogSort {
+    /// Category, then title.
+    #[default]
+    Title,
+    BenchmarkDate,
+    ReleaseNumber,
+    LastUpdated,
+}
+
+/// Filters and ordering for `list_catalog`; the default lists everything.
+#[derive(Debug, Clone, Default)]
+pub struct CatalogFilter {
+    pub category: Option<String>,
+    /// Only entries carrying this tag.
+    pub tag: Option<String>,
+    /// Inclusive bounds on `benchmark_date`.
+    pub released_after: Option<NaiveDate>,
+    pub released_before: Option<NaiveDate>,
+    /// Only entries parsed by an older release (see `parser::PARSER_VERSION`).
+    pub stale: bool,
+    /// Only entries with a rule implementing this SRG requirement.
+    pub srg_id: Option<String>,
+    pub sort: CatalogSort,
+    pub descending: bool,
+}
+
+/// Return catalog entries matching `filter`.
+pub async fn list_catalog(pool: &PgPool, filter: &CatalogFilter) -> Result<Vec<CatalogEntry>> {
+    let mut query: QueryBuilder<Postgres> = QueryBuilder::new("SELECT * FROM stigs_catalog WHERE TRUE");
+    if let Some(cat) = &filter.category {
+        query.push(" AND category = ").push_bind(cat.clone());
+    }
+    if let Some(tag) = &filter.tag {
+        query.push(" AND ").push_bind(tag.clone()).push(" = ANY(tags)");
+    }
+    if let Some(after) = filter.released_after {
+        query.push(" AND benchmark_date >= ").push_bind(after);
+    }
+    if let Some(before) = filter.released_before {
+        query.push(" AND benchmark_date <= ").push_bind(before);
+    }
+    if filter.stale {
+        query.push(" AND parser_version < ").push_bind(PARSER_VERSION as i32);
+    }
+    if let Some(srg_id) = &filter.srg_id {
+        query.push(" AND srg_ids @> ARRAY[").push_bind(srg_id.clone()).push("]");
+    }
+
+    let dir = if filter.descending { "DESC" } else { "ASC" };
+    query.push(match filter.sort {
+        CatalogSort::Title => format!(" ORDER BY category {dir}, title {dir}"),
+        CatalogSort::BenchmarkDate => format!(" ORDER BY benchmark_date {dir} NULLS LAST, title"),
+        CatalogSort::ReleaseNumber => {
+            format!(" ORDER BY release_number {dir} NULLS LAST, title")
         }
-    };
+        CatalogSort::LastUpdated => format!(" ORDER BY last_updated {dir}, title"),
+    });
+
+    let rows = query
+        .build_query_as::<CatalogEntry>()
+        .fetch_all(pool)
+        .await?;
     Ok(rows)
 }
 
+/// Look up one catalog entry.
+pub async fn get_catalog_entry(pool: &PgPool, id: &str) -> Result<Option<CatalogEntry>> {
+    let row = sqlx::query_as::<_, CatalogEntry>("SELECT * FROM stigs_catalog WHERE id = $1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+    Ok(row)
+}
+
 /// Count rows in the catalog (used by /api/health).
 pub async fn count_catalog(pool: &PgPool) -> Result<i64> {
     let row: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM stigs_catalog")
@@ -64,8 +213,12 @@ pub async fn upsert_catalog(pool: &PgPool, entry: &CatalogEntry) -> Result<()> {
     sqlx::query(
         r#"
         INSERT INTO stigs_catalog
-            (id, title, category, version, release_info, rule_count, json_path, last_updated)
-        VALUES ($1, $2, $3, $4, $5, $6, $7, NOW())
+            (id, title, category, version, release_info, rule_count, json_path, last_updated,
+             status, status_date, notice, front_matter, rear_matter, publisher, source, platforms,
+             release_number, benchmark_date, release_label, benchmark_id, tags, warnings,
+             parser_version, category_source, srg_ids)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, NOW(), $8, $9, $10, $11, $12, $13, $14, $15,
+                $16, $17, $18, $19, $20, $21, $22, $23, $24)
         ON CONFLICT (id) DO UPDATE SET
             title        = EXCLUDED.title,
             category     = EXCLUDED.category,
@@ -73,7 +226,24 @@ pub async fn upsert_catalog(pool: &PgPool, entry: &CatalogEntry) -> Result<()> {
             release_info = EXCLUDED.release_info,
             rule_count   = EXCLUDED.rule_count,
             json_path    = EXCLUDED.json_path,
-            last_updated = NOW()
+            last_updated = NOW(),
+            status       = EXCLUDED.status,
+            status_date  = EXCLUDED.status_date,
+            notice       = EXCLUDED.notice,
+            front_matter = EXCLUDED.front_matter,
+            rear_matter  = EXCLUDED.rear_matter,
+            publisher    = EXCLUDED.publisher,
+            source       = EXCLUDED.source,
+            platforms    = EXCLUDED.platforms,
+            release_number = EXCLUDED.release_number,
+            benchmark_date = EXCLUDED.benchmark_date,
+            release_label  = EXCLUDED.release_label,
+            benchmark_id   = EXCLUDED.benchmark_id,
+            tags           = EXCLUDED.tags,
+            warnings       = EXCLUDED.warnings,
+            parser_version = EXCLUDED.parser_version,
+            category_source = EXCLUDED.category_source,
+            srg_ids        = EXCLUDED.srg_ids
         "#,
     )
     .bind(&entry.id)
@@ -83,6 +253,235 @@ pub async fn upsert_catalog(pool: &PgPool, entry: &CatalogEntry) -> Result<()> {
     .bind(&entry.release_info)
     .bind(entry.rule_count)
     .bind(&entry.json_path)
+    .bind(&entry.status)
+    .bind(entry.status_date)
+    .bind(&entry.notice)
+    .bind(&entry.front_matter)
+    .bind(&entry.rear_matter)
+    .bind(&entry.publisher)
+    .bind(&entry.source)
+    .bind(&entry.platforms)
+    .bind(entry.release_number)
+    .bind(entry.benchmark_date)
+    .bind(&entry.release_label)
+    .bind(&entry.benchmark_id)
+    .bind(&entry.tags)
+    .bind(&entry.warnings)
+    .bind(entry.parser_version)
+    .bind(entry.category_source)
+    .bind(&entry.srg_ids)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Replace the category and tags of an existing catalog entry.
+pub async fn set_catalog_category(
+    pool: &PgPool,
+    id: &str,
+    category: &str,
+    tags: &[String],
+) -> Result<()> {
+    sqlx::query("UPDATE stigs_catalog SET category = $2, tags = $3 WHERE id = $1")
+        .bind(id)
+        .bind(category)
+        .bind(tags)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Record the parser version of the JSON behind an existing catalog entry.
+pub async fn set_parser_version(pool: &PgPool, id: &str, parser_version: i32) -> Result<()> {
+    sqlx::query("UPDATE stigs_catalog SET parser_version = $2 WHERE id = $1")
+        .bind(id)
+        .bind(parser_version)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Record the SRG ids of the rules behind an existing catalog entry.
+pub async fn set_srg_ids(pool: &PgPool, id: &str, srg_ids: &[String]) -> Result<()> {
+    sqlx::query("UPDATE stigs_catalog SET srg_ids = $2 WHERE id = $1")
+        .bind(id)
+        .bind(srg_ids)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Record the SCAP benchmark version/release and its parser warnings on an
+/// existing catalog entry.
+///
+/// Returns `false` when no entry with that id exists.
+pub async fn set_scap_benchmark(pool: &PgPool, id: &str, stig: &StigData) -> Result<bool> {
+    let result = sqlx::query(
+        "UPDATE stigs_catalog SET scap_version = $2, scap_release_info = $3, scap_warnings = $4 \
+         WHERE id = $1",
+    )
+    .bind(id)
+    .bind(&stig.version)
+    .bind(&stig.release_info)
+    .bind(Json(&stig.warnings))
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Fold catalog entry `old_id` into `new_id` and repoint checklists at it.
+///
+/// With `replace`, the `old_id` row is renamed to `new_id` (dropping any row
+/// already there) and its JSON path set to `json_path`; otherwise the
+/// `old_id` row is dropped in favour of the existing `new_id` row.
+pub async fn merge_catalog_entry(
+    pool: &PgPool,
+    old_id: &str,
+    new_id: &str,
+    replace: bool,
+    json_path: &str,
+) -> Result<()> {
+    let mut tx = pool.begin().await?;
+    if replace {
+        sqlx::query("DELETE FROM stigs_catalog WHERE id = $1")
+            .bind(new_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("UPDATE stigs_catalog SET id = $2, json_path = $3 WHERE id = $1")
+            .bind(old_id)
+            .bind(new_id)
+            .bind(json_path)
+            .execute(&mut *tx)
+            .await?;
+    } else {
+        sqlx::query("DELETE FROM stigs_catalog WHERE id = $1")
+            .bind(old_id)
+            .execute(&mut *tx)
+            .await?;
+    }
+    sqlx::query(
+        "UPDATE checklists SET stig_ids = array_replace(stig_ids, $1, $2) WHERE $1 = ANY(stig_ids)",
+    )
+    .bind(old_id)
+    .bind(new_id)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Find the catalog entry a checklist STIG was reviewed against.
+///
+/// Matches on benchmark id and version, preferring the same release; entries
+/// imported before benchmark ids were recorded are matched on title instead.
+pub async fn find_catalog_stig(
+    pool: &PgPool,
+    benchmark_id: &str,
+    title: &str,
+    version: &str,
+    release_info: &str,
+) -> Result<Option<String>> {
+    let row: Option<(String,)> = sqlx::query_as(
+        r#"
+        SELECT id FROM stigs_catalog
+        WHERE version = $3
+          AND ((benchmark_id <> '' AND benchmark_id = $1) OR (benchmark_id = '' AND title = $2))
+        ORDER BY (release_info = $4) DESC, last_updated DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(benchmark_id)
+    .bind(title)
+    .bind(version)
+    .bind(release_info)
+    .fetch_optional(pool)
+    .await?;
+    Ok(row.map(|(id,)| id))
+}
+
+/// Checklist row as stored in PostgreSQL and returned by GET /api/checklists.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ChecklistEntry {
+    pub id: String,
+    pub name: String,
+    pub host_name: String,
+    /// Format the checklist was imported from: `ckl`, `cklb` or `xccdf`.
+    pub source_format: String,
+    /// Catalog ids of the STIGs the checklist was matched to.
+    pub stig_ids: Vec<String>,
+    pub rule_count: i32,
+    pub json_path: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ChecklistEntry {
+    /// Build the row for a checklist stored at `json_path`.
+    pub fn from_checklist(
+        id: &str,
+        name: &str,
+        source_format: &str,
+        checklist: &Checklist,
+        json_path: &Path,
+    ) -> Self {
+        Self {
+            id: id.to_string(),
+            name: name.to_string(),
+            host_name: checklist.asset.host_name.clone(),
+            source_format: source_format.to_string(),
+            stig_ids: checklist.stigs.iter().filter_map(|s| s.catalog_id.clone()).collect(),
+            rule_count: checklist.stigs.iter().map(|s| s.stig.rules.len()).sum::<usize>() as i32,
+            json_path: json_path.to_string_lossy().into_owned(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+/// Return all checklists, most recently updated first.
+pub async fn list_checklists(pool: &PgPool) -> Result<Vec<ChecklistEntry>> {
+    let rows = sqlx::query_as::<_, ChecklistEntry>(
+        "SELECT * FROM checklists ORDER BY updated_at DESC",
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+/// Look up one checklist row.
+pub async fn get_checklist_entry(pool: &PgPool, id: &str) -> Result<Option<ChecklistEntry>> {
+    let row = sqlx::query_as::<_, ChecklistEntry>("SELECT * FROM checklists WHERE id = $1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await?;
+    Ok(row)
+}
+
+/// Upsert a checklist row — `created_at` is kept on update.
+pub async fn upsert_checklist(pool: &PgPool, entry: &ChecklistEntry) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO checklists
+            (id, name, host_name, source_format, stig_ids, rule_count, json_path, created_at, updated_at)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, NOW(), NOW())
+        ON CONFLICT (id) DO UPDATE SET
+            name          = EXCLUDED.name,
+            host_name     = EXCLUDED.host_name,
+            source_format = EXCLUDED.source_format,
+            stig_ids      = EXCLUDED.stig_ids,
+            rule_count    = EXCLUDED.rule_count,
+            json_path     = EXCLUDED.json_path,
+            updated_at    = NOW()
+        "#,
+    )
+    .bind(&entry.id)
+    .bind(&entry.name)
+    .bind(&entry.host_name)
+    .bind(&entry.source_format)
+    .bind(&entry.stig_ids)
+    .bind(entry.rule_count)
+    .bind(&entry.json_path)
     .execute(pool)
     .await?;
     Ok(())
@@ -0,0 +1,280 @@
+use quick_xml::escape::escape;
+use std::fmt::Write;
+use uuid::Uuid;
+
+use crate::parser::ckl::{status_to_ckl, Checklist, ChecklistStig};
+use crate::parser::{xccdf_severity, Rule};
+
+/// Write a checklist as a STIG Viewer 2 `.ckl`.
+///
+/// Element order and layout follow STIG Viewer 2.17, which some importers
+/// (eMASS, Evaluate-STIG) rely on: every ASSET field, the eleven STIG_INFO
+/// SI_DATA entries, and the full STIG_DATA attribute list per VULN.
+pub fn checklist_to_ckl(checklist: &Checklist) -> String {
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<!--DISA STIG Viewer :: 2.17-->\n");
+    out.push_str("<CHECKLIST>\n\t<ASSET>\n");
+
+    let asset = &checklist.asset;
+    let asset_fields = [
+        ("ROLE", or_default(asset.role.clone(), "None")),
+        (
+            "ASSET_TYPE",
+            or_default(asset.asset_type.clone(), "Computing"),
+        ),
+        ("MARKING", or_default(asset.marking.clone(), "CUI")),
+        ("HOST_NAME", asset.host_name.clone()),
+        ("HOST_IP", asset.host_ip.clone()),
+        ("HOST_MAC", asset.host_mac.clone()),
+        ("HOST_FQDN", asset.host_fqdn.clone()),
+        ("TARGET_COMMENT", asset.target_comment.clone()),
+        ("TECH_AREA", asset.tech_area.clone()),
+        ("TARGET_KEY", asset.target_key.clone()),
+        ("WEB_OR_DATABASE", asset.web_or_database.to_string()),
+        ("WEB_DB_SITE", asset.web_db_site.clone()),
+        ("WEB_DB_INSTANCE", asset.web_db_instance.clone()),
+    ];
+    for (tag, value) in asset_fields {
+        element(&mut out, 2, tag, &value);
+    }
+
+    out.push_str("\t</ASSET>\n\t<STIGS>\n");
+    for stig in &checklist.stigs {
+        write_istig(&mut out, stig, &asset.target_key);
+    }
+    out.push_str("\t</STIGS>\n</CHECKLIST>\n");
+    out
+}
+
+fn write_istig(out: &mut String, entry: &ChecklistStig, target_key: &str) {
+    let stig = &entry.stig;
+    let uuid = entry
+        .uuid
+        .clone()
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+    out.push_str("\t\t<iSTIG>\n\t\t\t<STIG_INFO>\n");
+    let stig_info = [
+        ("version", stig.version.clone()),
+        (
+            "classification",
+            entry
+                .classification
+                .clone()
+                .unwrap_or_else(|| "UNCLASSIFIED".into()),
+        ),
+        ("customname", String::new()),
+        ("stigid", stig.benchmark_id.clone()),
+        ("description", stig.description.clone()),
+        ("filename", entry.filename.clone().unwrap_or_default()),
+        ("releaseinfo", stig.release_info.clone()),
+        ("title", stig.title.clone()),
+        ("uuid", uuid.clone()),
+        (
+            "notice",
+            stig.notices
+                .first()
+                .map_or_else(|| "terms-of-use".into(), |n| n.id.clone()),
+        ),
+        (
+            "source",
+            stig.references
+                .first()
+                .map_or_else(|| "STIG.DOD.MIL".into(), |r| r.source.clone()),
+        ),
+    ];
+    for (name, value) in stig_info {
+        out.push_str("\t\t\t\t<SI_DATA>\n");
+        element(out, 5, "SID_NAME", name);
+        // STIG Viewer omits SID_DATA entirely for empty values
+        if !value.is_empty() {
+            element(out, 5, "SID_DATA", &value);
+        }
+        out.push_str("\t\t\t\t</SI_DATA>\n");
+    }
+    out.push_str("\t\t\t</STIG_INFO>\n");
+
+    let stig_ref = format!(
+        "{} :: Version {}, {}",
+        stig.title, stig.version, stig.release_info
+    );
+    for rule in &stig.rules {
+        write_vuln(out, rule, &uuid, &stig_ref, target_key);
+    }
+    out.push_str("\t\t</iSTIG>\n");
+}
+
+fn write_vuln(out: &mut String, rule: &Rule, stig_uuid: &str, stig_ref: &str, target_key: &str) {
+    let text = |value: &Option<String>| value.clone().unwrap_or_default();
+    let attributes = [
+        ("Vuln_Num", rule.group_id.clone()),
+        ("Severity", xccdf_severity(&rule.severity).to_string()),
+        ("Group_Title", rule.group_title.clone()),
+        ("Rule_ID", rule.id.clone()),
+        ("Rule_Ver", rule.stig_id.clone()),
+        ("Rule_Title", rule.title.clone()),
+        ("Vuln_Discuss", rule.description.clone()),
+        ("IA_Controls", text(&rule.ia_controls)),
+        ("Check_Content", rule.check_text.clone()),
+        ("Fix_Text", rule.fix_text.clone()),
+        ("False_Positives", text(&rule.false_positives)),
+        ("False_Negatives", text(&rule.false_negatives)),
+        (
+            "Documentable",
+            or_default(text(&rule.documentable), "false"),
+        ),
+        ("Mitigations", text(&rule.mitigations)),
+        ("Potential_Impact", text(&rule.potential_impacts)),
+        ("Third_Party_Tools", text(&rule.third_party_tools)),
+        ("Mitigation_Control", text(&rule.mitigation_control)),
+        ("Responsibility", text(&rule.responsibility)),
+        (
+            "Security_Override_Guidance",
+            text(&rule.severity_override_guidance),
+        ),
+        (
+            "Check_Content_Ref",
+            rule.check_content_ref
+                .as_ref()
+                .map_or_else(|| "M".into(), |r| r.name.clone()),
+        ),
+        ("Weight", or_default(text(&rule.weight), "10.0")),
+        ("Class", "Unclass".to_string()),
+        ("STIGRef", stig_ref.to_string()),
+        (
+            "TargetKey",
+            rule.references
+                .first()
+                .map_or_else(|| target_key.to_string(), |r| r.identifier.clone()),
+        ),
+        ("STIG_UUID", stig_uuid.to_string()),
+    ];
+
+    out.push_str("\t\t\t<VULN>\n");
+    for (name, value) in attributes {
+        stig_data(out, name, &value);
+    }
+    for legacy_id in &rule.legacy_ids {
+        stig_data(out, "LEGACY_ID", legacy_id);
+    }
+    for cci in &rule.cci_ids {
+        stig_data(out, "CCI_REF", cci);
+    }
+    element(out, 4, "STATUS", status_to_ckl(&rule.status));
+    element(out, 4, "FINDING_DETAILS", &rule.finding_details);
+    element(out, 4, "COMMENTS", &rule.comments);
+    element(
+        out,
+        4,
+        "SEVERITY_OVERRIDE",
+        rule.severity_override
+            .as_deref()
+            .map(xccdf_severity)
+            .unwrap_or_default(),
+    );
+    element(
+        out,
+        4,
+        "SEVERITY_JUSTIFICATION",
+        rule.severity_justification.as_deref().unwrap_or_default(),
+    );
+    out.push_str("\t\t\t</VULN>\n");
+}
+
+fn stig_data(out: &mut String, name: &str, value: &str) {
+    out.push_str("\t\t\t\t<STIG_DATA>\n");
+    element(out, 5, "VULN_ATTRIBUTE", name);
+    element(out, 5, "ATTRIBUTE_DATA", value);
+    out.push_str("\t\t\t\t</STIG_DATA>\n");
+}
+
+/// `<tag>value</tag>` on its own line, indented with `depth` tabs.
+fn element(out: &mut String, depth: usize, tag: &str, value: &str) {
+    let _ = writeln!(
+        out,
+        "{}<{tag}>{}</{tag}>",
+        "\t".repeat(depth),
+        escape(value)
+    );
+}
+
+/// `value`, or `fallback` when it is empty.
+fn or_default(value: String, fallback: &str) -> String {
+    if value.is_empty() {
+        fallback.to_string()
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ckl::{parse_ckl_reader, Asset};
+    use crate::parser::StigData;
+
+    fn rule(id: &str, status: &str) -> Rule {
+        Rule {
+            id: format!("SV-{id}r1_rule"),
+            stig_id: format!("CNTR-K8-{id}"),
+            group_id: format!("V-{id}"),
+            title: "Use TLS <1.3> & later".to_string(),
+            severity: "CAT I".to_string(),
+            check_text: "Run:\n\ngrep tls *".to_string(),
+            cci_ids: vec!["CCI-000068".to_string(), "CCI-001453".to_string()],
+            status: status.to_string(),
+            finding_details: "not set".to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn exported_ckl_reads_back() {
+        let checklist = Checklist {
+            asset: Asset {
+                host_name: "k8s-cp01".to_string(),
+                ..Default::default()
+            },
+            stigs: vec![ChecklistStig {
+                stig: StigData {
+                    benchmark_id: "Kubernetes_STIG".to_string(),
+                    title: "Kubernetes STIG".to_string(),
+                    version: "2".to_string(),
+                    rules: vec![
+                        rule("000150", "open"),
+                        rule("000160", "not_a_finding"),
+                        rule("000170", "not_applicable"),
+                        rule("000180", "not_reviewed"),
+                    ],
+                    ..Default::default()
+                },
+                ..Default::default()
+            }],
+            review: None,
+        };
+
+        let ckl = checklist_to_ckl(&checklist);
+        assert!(ckl.contains("<STATUS>NotAFinding</STATUS>"));
+        let reread = parse_ckl_reader(ckl.as_bytes()).unwrap();
+        assert_eq!(reread.asset.host_name, "k8s-cp01");
+        assert_eq!(reread.asset.role, "None");
+
+        let stig = &reread.stigs[0].stig;
+        assert_eq!(stig.benchmark_id, "Kubernetes_STIG");
+        let statuses: Vec<&str> = stig.rules.iter().map(|r| r.status.as_str()).collect();
+        assert_eq!(
+            statuses,
+            ["open", "not_a_finding", "not_applicable", "not_reviewed"]
+        );
+
+        let first = &stig.rules[0];
+        assert_eq!(first.id, "SV-000150r1_rule");
+        assert_eq!(first.stig_id, "CNTR-K8-000150");
+        assert_eq!(first.title, "Use TLS <1.3> & later");
+        assert_eq!(first.severity, "CAT I");
+        assert_eq!(first.check_text, "Run:\n\ngrep tls *");
+        assert_eq!(first.cci_ids, ["CCI-000068", "CCI-001453"]);
+        assert_eq!(first.finding_details, "not set");
+    }
+}
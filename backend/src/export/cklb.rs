@@ -0,0 +1,257 @@
+use uuid::Uuid;
+
+use crate::parser::ckl::Checklist;
+use crate::parser::cklb::{Cklb, CklbCheckContentRef, CklbGroup, CklbRule, CklbStig, CklbTarget};
+use crate::parser::{xccdf_severity, Rule};
+
+/// Build a STIG Viewer 3 `.cklb` document from a stored checklist.
+///
+/// Uuids read from an imported CKLB/CKL are reused so the file round-trips;
+/// STIGs and rules that never had one get a fresh uuid.
+pub fn checklist_to_cklb(id: &str, title: &str, checklist: &Checklist) -> Cklb {
+    let asset = &checklist.asset;
+    let text = |value: &str| Some(value.to_string());
+
+    let stigs = checklist
+        .stigs
+        .iter()
+        .map(|entry| {
+            let stig = &entry.stig;
+            let stig_uuid = entry
+                .uuid
+                .clone()
+                .unwrap_or_else(|| Uuid::new_v4().to_string());
+            let stig_ref = format!(
+                "{} :: Version {}, {}",
+                stig.title, stig.version, stig.release_info
+            );
+            let target_key = stig
+                .rules
+                .iter()
+                .find_map(|r| {
+                    r.references
+                        .first()
+                        .map(|reference| reference.identifier.clone())
+                })
+                .unwrap_or_else(|| asset.target_key.clone());
+
+            CklbStig {
+                stig_name: text(&stig.title),
+                display_name: text(
+                    stig.title
+                        .trim_end_matches(" Security Technical Implementation Guide")
+                        .trim_end_matches(" STIG"),
+                ),
+                stig_id: text(&stig.benchmark_id),
+                release_info: text(&stig.release_info),
+                version: text(&stig.version),
+                uuid: Some(stig_uuid.clone()),
+                reference_identifier: Some(target_key.clone()).filter(|k| !k.is_empty()),
+                size: stig.rules.len(),
+                rules: stig
+                    .rules
+                    .iter()
+                    .map(|rule| rule_to_cklb(rule, &stig_uuid, &target_key, &stig_ref))
+                    .collect(),
+            }
+        })
+        .collect();
+
+    Cklb {
+        title: text(title),
+        id: text(id),
+        active: false,
+        mode: 1,
+        has_path: true,
+        target_data: CklbTarget {
+            target_type: text(if asset.asset_type.is_empty() {
+                "Computing"
+            } else {
+                &asset.asset_type
+            }),
+            host_name: text(&asset.host_name),
+            ip_address: text(&asset.host_ip),
+            mac_address: text(&asset.host_mac),
+            fqdn: text(&asset.host_fqdn),
+            comments: text(&asset.target_comment),
+            role: text(if asset.role.is_empty() {
+                "None"
+            } else {
+                &asset.role
+            }),
+            is_web_database: asset.web_or_database,
+            technology_area: text(&asset.tech_area),
+            web_db_site: text(&asset.web_db_site),
+            web_db_instance: text(&asset.web_db_instance),
+            classification: Some(asset.marking.clone()).filter(|m| !m.is_empty()),
+        },
+        stigs,
+        cklb_version: text("1.0"),
+    }
+}
+
+fn rule_to_cklb(rule: &Rule, stig_uuid: &str, target_key: &str, stig_ref: &str) -> CklbRule {
+    let overrides = match &rule.severity_override {
+        Some(severity) => serde_json::json!({
+            "severity": {
+                "severity": xccdf_severity(severity),
+                "reason": rule.severity_justification.clone().unwrap_or_default(),
+            }
+        }),
+        None => serde_json::json!({}),
+    };
+
+    CklbRule {
+        uuid: Some(
+            rule.uuid
+                .clone()
+                .unwrap_or_else(|| Uuid::new_v4().to_string()),
+        ),
+        stig_uuid: Some(stig_uuid.to_string()),
+        target_key: Some(target_key.to_string()).filter(|k| !k.is_empty()),
+        stig_ref: Some(stig_ref.to_string()),
+        group_id: Some(rule.group_id.clone()),
+        rule_id: Some(rule.id.trim_end_matches("_rule").to_string()),
+        rule_id_src: Some(rule.id.clone()),
+        weight: Some(rule.weight.clone().unwrap_or_else(|| "10.0".to_string())),
+        classification: Some("Unclassified".to_string()),
+        severity: Some(xccdf_severity(&rule.severity).to_string()),
+        rule_version: Some(rule.stig_id.clone()),
+        group_title: Some(rule.group_title.clone()),
+        rule_title: Some(rule.title.clone()),
+        fix_text: Some(rule.fix_text.clone()),
+        false_positives: rule.false_positives.clone(),
+        false_negatives: rule.false_negatives.clone(),
+        discussion: Some(rule.description.clone()),
+        check_content: Some(rule.check_text.clone()),
+        documentable: rule.documentable.clone(),
+        mitigations: rule.mitigations.clone(),
+        potential_impacts: rule.potential_impacts.clone(),
+        third_party_tools: rule.third_party_tools.clone(),
+        mitigation_control: rule.mitigation_control.clone(),
+        responsibility: rule.responsibility.clone(),
+        security_override_guidance: rule.severity_override_guidance.clone(),
+        ia_controls: rule.ia_controls.clone(),
+        check_content_ref: rule
+            .check_content_ref
+            .as_ref()
+            .map(|r| CklbCheckContentRef {
+                href: Some(r.href.clone()),
+                name: Some(r.name.clone()),
+            }),
+        legacy_ids: rule.legacy_ids.clone(),
+        ccis: rule.cci_ids.clone(),
+        group_tree: vec![CklbGroup {
+            id: Some(rule.group_id.clone()),
+            title: Some(rule.group_title.clone()),
+            description: Some(format!(
+                "<GroupDescription>{}</GroupDescription>",
+                rule.group_description.as_deref().unwrap_or_default()
+            )),
+        }],
+        status: Some(rule.status.clone()),
+        overrides,
+        comments: Some(rule.comments.clone()),
+        finding_details: Some(rule.finding_details.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::cklb::parse_cklb_reader;
+
+    const CKLB: &str = r#"{
+      "title": "k8s-cp01",
+      "id": "8b5c9d8e-0000-4000-8000-000000000000",
+      "target_data": {"host_name": "k8s-cp01", "role": "Member Server"},
+      "stigs": [{
+        "stig_name": "Kubernetes Security Technical Implementation Guide",
+        "stig_id": "Kubernetes_STIG",
+        "release_info": "Release: 5 Benchmark Date: 05 Jan 2026",
+        "version": "2",
+        "uuid": "3b1a2c4d-0000-4000-8000-000000000001",
+        "reference_identifier": "5289",
+        "rules": [{
+          "uuid": "0c8f0000-0000-4000-8000-000000000002",
+          "group_id": "V-242376",
+          "rule_id_src": "SV-242376r960759_rule",
+          "severity": "medium",
+          "rule_version": "CNTR-K8-000150",
+          "rule_title": "Use TLS 1.2.",
+          "group_tree": [{
+            "id": "V-242376",
+            "title": "SRG-APP-000014-CTR-000035",
+            "description": "<GroupDescription>Transport security</GroupDescription>"
+          }],
+          "status": "open",
+          "overrides": {"severity": {"severity": "low", "reason": "Isolated network"}},
+          "comments": "Ticket 42",
+          "finding_details": "tls-min-version is unset"
+        }, {
+          "uuid": "0c8f0000-0000-4000-8000-000000000003",
+          "group_id": "V-242377",
+          "rule_id_src": "SV-242377r960759_rule",
+          "severity": "high",
+          "rule_version": "CNTR-K8-000160",
+          "status": "not_a_finding",
+          "overrides": {}
+        }]
+      }]
+    }"#;
+
+    #[test]
+    fn exported_cklb_reads_back() {
+        let imported = parse_cklb_reader(CKLB.as_bytes()).unwrap();
+        let cklb = checklist_to_cklb(
+            "8b5c9d8e-0000-4000-8000-000000000000",
+            "k8s-cp01",
+            &imported,
+        );
+        let json = serde_json::to_string(&cklb).unwrap();
+        let checklist = parse_cklb_reader(json.as_bytes()).unwrap();
+
+        assert_eq!(checklist.asset.host_name, "k8s-cp01");
+        assert_eq!(checklist.asset.role, "Member Server");
+        let stig = &checklist.stigs[0];
+        assert_eq!(
+            stig.uuid.as_deref(),
+            Some("3b1a2c4d-0000-4000-8000-000000000001")
+        );
+        assert_eq!(stig.stig.benchmark_id, "Kubernetes_STIG");
+
+        let [first, second] = &stig.stig.rules[..] else {
+            panic!("expected two rules, got {}", stig.stig.rules.len());
+        };
+        assert_eq!(
+            first.uuid.as_deref(),
+            Some("0c8f0000-0000-4000-8000-000000000002")
+        );
+        assert_eq!(first.id, "SV-242376r960759_rule");
+        assert_eq!(first.stig_id, "CNTR-K8-000150");
+        assert_eq!(first.status, "open");
+        assert_eq!(first.severity, "CAT II");
+        assert_eq!(first.severity_override.as_deref(), Some("CAT III"));
+        assert_eq!(
+            first.severity_justification.as_deref(),
+            Some("Isolated network")
+        );
+        assert_eq!(first.comments, "Ticket 42");
+        assert_eq!(first.finding_details, "tls-min-version is unset");
+        assert_eq!(first.group_title, "SRG-APP-000014-CTR-000035");
+        assert_eq!(
+            first.group_description.as_deref(),
+            Some("Transport security")
+        );
+
+        assert_eq!(
+            second.uuid.as_deref(),
+            Some("0c8f0000-0000-4000-8000-000000000003")
+        );
+        assert_eq!(second.status, "not_a_finding");
+        assert_eq!(second.severity, "CAT I");
+        assert_eq!(second.severity_override, None);
+        assert_eq!(second.finding_details, "");
+        assert_eq!(second.group_description, None);
+    }
+}
@@ -0,0 +1,6 @@
+//! Writers that turn stored STIGs and checklists into files other tools read.
+
+pub mod ckl;
+pub mod cklb;
+pub mod poam;
+pub mod xccdf;
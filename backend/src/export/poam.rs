@@ -0,0 +1,309 @@
+use anyhow::Result;
+use chrono::{Duration, NaiveDate};
+use rust_xlsxwriter::{Format, FormatBorder, Workbook};
+use serde::Deserialize;
+
+use crate::cci::CciList;
+use crate::parser::ckl::Checklist;
+use crate::parser::Rule;
+
+/// Column headings of the eMASS POA&M import template, in order.
+const HEADERS: [&str; 25] = [
+    "Control Vulnerability Description",
+    "Security Control Number (NC/NA controls only)",
+    "Office/Org",
+    "Security Checks",
+    "Resources Required",
+    "Scheduled Completion Date",
+    "Milestone ID",
+    "Milestone with Completion Dates",
+    "Milestone Changes",
+    "Source Identifying Vulnerability",
+    "Status",
+    "Comments",
+    "Raw Severity",
+    "Devices Affected",
+    "Mitigations",
+    "Predisposing Conditions",
+    "Severity",
+    "Relevance of Threat",
+    "Threat Description",
+    "Likelihood",
+    "Impact",
+    "Impact Description",
+    "Residual Risk Level",
+    "Recommendations",
+    "Resulting Residual Risk after Proposed Mitigations",
+];
+
+/// Row of the column headings; the system information block sits above it.
+const HEADER_ROW: u32 = 6;
+
+/// POA&M item statuses eMASS accepts on import.
+pub const STATUSES: [&str; 5] = [
+    "Ongoing",
+    "Risk Accepted",
+    "Completed",
+    "Not Applicable",
+    "Archived",
+];
+
+/// System information and row selection for a POA&M export.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PoamOptions {
+    /// Also list Not_Reviewed rules, not only Open ones.
+    pub include_not_reviewed: bool,
+    /// System / Project Name; defaults to the checklist name.
+    pub system: Option<String>,
+    pub exported_by: Option<String>,
+    pub component: Option<String>,
+    pub office: Option<String>,
+    pub poc_name: Option<String>,
+    pub poc_phone: Option<String>,
+    pub poc_email: Option<String>,
+    /// Milestone written for every item, with its scheduled completion date.
+    pub milestone: Option<String>,
+    /// Status of every item, one of [`STATUSES`].
+    pub status: Option<String>,
+}
+
+/// Build an eMASS POA&M workbook with one item per open rule.
+///
+/// Each item is scheduled for completion 30, 90 or 180 days after `today`
+/// for CAT I, II and III findings; raw severity is the STIG's category and
+/// the residual risk reflects any severity override.  Milestone and status
+/// are left for the ISSO unless `options` sets them.  CCIs `cci` cannot
+/// resolve are listed next to the control numbers.
+pub fn checklist_to_poam(
+    checklist: &Checklist,
+    options: &PoamOptions,
+    cci: &CciList,
+    today: NaiveDate,
+) -> Result<Vec<u8>> {
+    let mut workbook = Workbook::new();
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("POA&M")?;
+
+    let bold = Format::new().set_bold();
+    let heading = Format::new()
+        .set_bold()
+        .set_text_wrap()
+        .set_background_color("D9D9D9")
+        .set_border(FormatBorder::Thin);
+    let cell = Format::new().set_text_wrap().set_border(FormatBorder::Thin);
+
+    let date = |d: NaiveDate| d.format("%m/%d/%Y").to_string();
+    let text = |value: &Option<String>| value.clone().unwrap_or_default();
+    let system_info = [
+        [
+            ("Date Exported:", date(today)),
+            ("System Type:", String::new()),
+        ],
+        [
+            ("Exported By:", text(&options.exported_by)),
+            ("OMB Project ID:", String::new()),
+        ],
+        [
+            ("DoD Component:", text(&options.component)),
+            ("POC Name:", text(&options.poc_name)),
+        ],
+        [
+            ("System / Project Name:", text(&options.system)),
+            ("POC Phone:", text(&options.poc_phone)),
+        ],
+        [
+            ("DoD IT Registration No:", String::new()),
+            ("POC E-Mail:", text(&options.poc_email)),
+        ],
+    ];
+    for (row, pairs) in system_info.iter().enumerate() {
+        for (block, (label, value)) in pairs.iter().enumerate() {
+            let col = (block * 3) as u16;
+            sheet.write_string_with_format(row as u32, col, *label, &bold)?;
+            sheet.write_string(row as u32, col + 1, value)?;
+        }
+    }
+
+    for (col, header) in HEADERS.iter().enumerate() {
+        sheet.write_string_with_format(HEADER_ROW, col as u16, *header, &heading)?;
+        sheet.set_column_width(col as u16, if col == 0 || col == 23 { 60 } else { 20 })?;
+    }
+    sheet.set_freeze_panes(HEADER_ROW + 1, 0)?;
+
+    let asset = &checklist.asset;
+    let device = [&asset.host_name, &asset.host_fqdn, &asset.host_ip]
+        .into_iter()
+        .find(|d| !d.is_empty())
+        .cloned()
+        .unwrap_or_default();
+
+    let mut row = HEADER_ROW + 1;
+    for entry in &checklist.stigs {
+        let stig = &entry.stig;
+        let source = format!(
+            "{} :: Version {}, {}",
+            stig.title, stig.version, stig.release_info
+        );
+        let items = stig.rules.iter().filter(|r| match r.status.as_str() {
+            "open" => true,
+            "not_a_finding" | "not_applicable" => false,
+            _ => options.include_not_reviewed,
+        });
+        for rule in items {
+            let due = date(today + Duration::days(remediation_days(&rule.severity)));
+            let residual = residual_risk(rule);
+            let mut comments = rule.comments.clone();
+            if let Some(justification) = &rule.severity_justification {
+                if !comments.is_empty() {
+                    comments.push_str("\n\n");
+                }
+                comments.push_str(&format!("Severity override: {justification}"));
+            }
+            let (numbers, unmapped) = cci.control_numbers(&rule.cci_ids);
+            let mut controls = numbers.join(", ");
+            if !unmapped.is_empty() {
+                if !controls.is_empty() {
+                    controls.push('\n');
+                }
+                controls.push_str(&format!("Unmapped: {}", unmapped.join(", ")));
+            }
+            let (milestone_id, milestone) = match options.milestone.as_deref() {
+                Some(milestone) if !milestone.trim().is_empty() => {
+                    ("1".to_string(), format!("{} {due}", milestone.trim()))
+                }
+                _ => (String::new(), String::new()),
+            };
+            let description = if rule.finding_details.is_empty() {
+                rule.title.clone()
+            } else {
+                format!("{}\n\n{}", rule.title, rule.finding_details)
+            };
+
+            let values = [
+                description,
+                controls,
+                text(&options.office),
+                format!("{} / {}", rule.group_id, rule.stig_id),
+                String::new(),
+                due.clone(),
+                milestone_id,
+                milestone,
+                String::new(),
+                source.clone(),
+                text(&options.status),
+                comments,
+                rule.severity.trim_start_matches("CAT ").to_string(),
+                device.clone(),
+                text(&rule.mitigations),
+                String::new(),
+                residual.to_string(),
+                String::new(),
+                String::new(),
+                String::new(),
+                String::new(),
+                text(&rule.potential_impacts),
+                residual.to_string(),
+                rule.fix_text.clone(),
+                String::new(),
+            ];
+            for (col, value) in values.iter().enumerate() {
+                sheet.write_string_with_format(row, col as u16, value, &cell)?;
+            }
+            row += 1;
+        }
+    }
+
+    Ok(workbook.save_to_buffer()?)
+}
+
+/// Days from export to the scheduled completion of a finding.
+fn remediation_days(severity: &str) -> i64 {
+    match severity {
+        "CAT I" => 30,
+        "CAT III" => 180,
+        _ => 90,
+    }
+}
+
+/// eMASS risk level of a rule after any severity override.
+fn residual_risk(rule: &Rule) -> &'static str {
+    match rule.severity_override.as_deref().unwrap_or(&rule.severity) {
+        "CAT I" => "High",
+        "CAT III" => "Low",
+        _ => "Moderate",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::ckl::ChecklistStig;
+    use crate::parser::StigData;
+    use std::io::{Cursor, Read};
+
+    fn checklist() -> Checklist {
+        let rule = Rule {
+            group_id: "V-242376".to_string(),
+            stig_id: "CNTR-K8-000150".to_string(),
+            title: "Use TLS 1.2".to_string(),
+            severity: "CAT I".to_string(),
+            cci_ids: vec!["CCI-000068".to_string(), "CCI-999999".to_string()],
+            status: "open".to_string(),
+            ..Default::default()
+        };
+        Checklist {
+            stigs: vec![ChecklistStig {
+                stig: StigData {
+                    rules: vec![rule],
+                    ..Default::default()
+                },
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    /// The workbook's shared strings, which hold every cell value written.
+    fn strings(options: &PoamOptions) -> String {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 1).unwrap();
+        let xlsx = checklist_to_poam(&checklist(), options, &CciList::builtin(), today).unwrap();
+        let mut archive = zip::ZipArchive::new(Cursor::new(xlsx)).unwrap();
+        let mut xml = String::new();
+        archive
+            .by_name("xl/sharedStrings.xml")
+            .unwrap()
+            .read_to_string(&mut xml)
+            .unwrap();
+        xml
+    }
+
+    #[test]
+    fn lists_unmapped_ccis_with_the_controls() {
+        assert!(strings(&PoamOptions::default()).contains("IA-7\nUnmapped: CCI-999999"));
+    }
+
+    #[test]
+    fn milestone_and_status_are_left_empty() {
+        let xml = strings(&PoamOptions::default());
+        assert!(!xml.contains("Ongoing"), "{xml}");
+        assert!(!xml.contains("Apply the STIG fix"), "{xml}");
+        // Scheduled completion is still 30 days out for CAT I
+        assert!(xml.contains("10/31/2026"), "{xml}");
+    }
+
+    #[test]
+    fn milestone_and_status_come_from_the_options() {
+        let options = PoamOptions {
+            milestone: Some("Enable TLS 1.2 on the API server".to_string()),
+            status: Some("Risk Accepted".to_string()),
+            ..Default::default()
+        };
+        let xml = strings(&options);
+        assert!(
+            xml.contains("Enable TLS 1.2 on the API server 10/31/2026"),
+            "{xml}"
+        );
+        assert!(xml.contains("Risk Accepted"), "{xml}");
+    }
+}
@@ -0,0 +1,253 @@
+use quick_xml::escape::escape;
+use std::fmt::Write;
+
+use crate::parser::ckl::{Asset, ChecklistStig, Review};
+use crate::parser::results::result_from_status;
+use crate::parser::{short_id, xccdf_severity};
+
+const XCCDF_NS: &str = "http://checklists.nist.gov/xccdf/1.2";
+const FACT: &str = "urn:scap:fact:asset:identifier:";
+
+/// Write one STIG of a checklist as a standalone XCCDF 1.2 `<TestResult>`.
+///
+/// The result references the benchmark by the id and version the parser
+/// recorded; ids from XCCDF 1.1 benchmarks get the `xccdf_mil.disa.stig_`
+/// prefix XCCDF 1.2 requires, so SCAP tools can resolve them against the
+/// DISA SCAP benchmark.  `stig.benchmark_id`, `review.start_time` and
+/// `review.end_time` must be set.
+pub fn checklist_to_xccdf(asset: &Asset, entry: &ChecklistStig, review: &Review) -> String {
+    let stig = &entry.stig;
+    let benchmark_id = xccdf12_id("benchmark", &stig.benchmark_id);
+    let start_time = review.start_time.as_deref().unwrap_or_default();
+    let end_time = review.end_time.as_deref().unwrap_or_default();
+
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        out,
+        "<TestResult xmlns=\"{XCCDF_NS}\" id=\"{}\" start-time=\"{}\" end-time=\"{}\" version=\"{}\">",
+        escape(testresult_id(&stig.benchmark_id)),
+        escape(start_time),
+        escape(end_time),
+        escape(&stig.version),
+    );
+    let _ = writeln!(
+        out,
+        "  <benchmark href=\"#{0}\" id=\"{0}\"/>",
+        escape(&benchmark_id),
+    );
+    element(
+        &mut out,
+        1,
+        "title",
+        &format!(
+            "{} :: Version {}, {}",
+            stig.title, stig.version, stig.release_info
+        ),
+    );
+    if !review.identity.is_empty() {
+        let _ = writeln!(
+            out,
+            "  <identity authenticated=\"false\" privileged=\"false\">{}</identity>",
+            escape(&review.identity),
+        );
+    }
+
+    let target = [&asset.host_name, &asset.host_fqdn, &asset.host_ip]
+        .into_iter()
+        .find(|t| !t.is_empty())
+        .map_or("unknown", |t| t.as_str());
+    element(&mut out, 1, "target", target);
+    if !asset.host_ip.is_empty() {
+        element(&mut out, 1, "target-address", &asset.host_ip);
+    }
+    let facts = [
+        ("host_name", &asset.host_name),
+        ("fqdn", &asset.host_fqdn),
+        ("ipv4", &asset.host_ip),
+        ("mac", &asset.host_mac),
+    ];
+    if facts.iter().any(|(_, value)| !value.is_empty()) {
+        out.push_str("  <target-facts>\n");
+        for (name, value) in facts.iter().filter(|(_, value)| !value.is_empty()) {
+            let _ = writeln!(
+                out,
+                "    <fact name=\"{FACT}{name}\" type=\"string\">{}</fact>",
+                escape(value.as_str())
+            );
+        }
+        out.push_str("  </target-facts>\n");
+    }
+
+    let (mut passed, mut failed) = (0, 0);
+    for rule in &stig.rules {
+        let result = result_from_status(&rule.status);
+        match result {
+            "pass" => passed += 1,
+            "fail" => failed += 1,
+            _ => {}
+        }
+        let severity = rule.severity_override.as_deref().unwrap_or(&rule.severity);
+        let _ = writeln!(
+            out,
+            "  <rule-result idref=\"{}\" time=\"{}\" severity=\"{}\" weight=\"{}\">",
+            escape(xccdf12_id("rule", &rule.id)),
+            escape(end_time),
+            xccdf_severity(severity),
+            escape(rule.weight.as_deref().unwrap_or("10.0")),
+        );
+        element(&mut out, 2, "result", result);
+        for cci in &rule.cci_ids {
+            let _ = writeln!(
+                out,
+                "    <ident system=\"http://cyber.mil/cci\">{}</ident>",
+                escape(cci)
+            );
+        }
+        if !rule.finding_details.is_empty() {
+            element(&mut out, 2, "message", &rule.finding_details);
+        }
+        out.push_str("  </rule-result>\n");
+    }
+
+    // Default model: percentage of the decided rules that passed
+    let score = if passed + failed == 0 {
+        0.0
+    } else {
+        100.0 * passed as f64 / (passed + failed) as f64
+    };
+    let _ = writeln!(
+        out,
+        "  <score system=\"urn:xccdf:scoring:default\" maximum=\"100\">{score:.2}</score>",
+    );
+    out.push_str("</TestResult>\n");
+    out
+}
+
+/// `xccdf_mil.disa.stig_<kind>_<id>`, unless `id` already is an XCCDF 1.2 id.
+fn xccdf12_id(kind: &str, id: &str) -> String {
+    if id.starts_with("xccdf_") {
+        id.to_string()
+    } else {
+        format!("xccdf_mil.disa.stig_{kind}_{id}")
+    }
+}
+
+/// `xccdf_mil.disa.stig_testresult_<benchmark>`, named after the benchmark
+/// without its XCCDF 1.2 prefix so it never collides with the benchmark's id.
+fn testresult_id(benchmark_id: &str) -> String {
+    let slug: String = short_id(benchmark_id.to_string())
+        .chars()
+        .map(|c| if c.is_whitespace() { '_' } else { c })
+        .collect();
+    format!("xccdf_mil.disa.stig_testresult_{slug}")
+}
+
+/// `<tag>value</tag>` on its own line, indented by `depth` levels.
+fn element(out: &mut String, depth: usize, tag: &str, value: &str) {
+    let _ = writeln!(
+        out,
+        "{}<{tag}>{}</{tag}>",
+        "  ".repeat(depth),
+        escape(value)
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::results::parse_results_reader;
+    use crate::parser::{Rule, StigData};
+
+    fn entry(benchmark_id: &str) -> ChecklistStig {
+        let rule = |id: &str, status: &str| Rule {
+            id: format!("SV-{id}r1_rule"),
+            severity: "high".to_string(),
+            cci_ids: vec!["CCI-000068".to_string()],
+            status: status.to_string(),
+            ..Default::default()
+        };
+        ChecklistStig {
+            stig: StigData {
+                benchmark_id: benchmark_id.to_string(),
+                title: "Kubernetes STIG".to_string(),
+                version: "2".to_string(),
+                rules: vec![
+                    rule("1", "open"),
+                    rule("2", "not_a_finding"),
+                    rule("3", "not_reviewed"),
+                ],
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn review() -> Review {
+        Review {
+            identity: r"CORP\jdoe".to_string(),
+            start_time: Some("2026-10-01T10:00:00Z".to_string()),
+            end_time: Some("2026-10-01T10:05:00Z".to_string()),
+        }
+    }
+
+    #[test]
+    fn testresult_id_is_never_the_benchmark_id() {
+        assert_eq!(
+            testresult_id("Kubernetes_STIG"),
+            "xccdf_mil.disa.stig_testresult_Kubernetes_STIG"
+        );
+        assert_eq!(
+            testresult_id("xccdf_mil.disa.stig_benchmark_Kubernetes_STIG"),
+            "xccdf_mil.disa.stig_testresult_Kubernetes_STIG"
+        );
+        assert_eq!(
+            testresult_id("My STIG"),
+            "xccdf_mil.disa.stig_testresult_My_STIG"
+        );
+    }
+
+    #[test]
+    fn xccdf12_id_adds_the_prefix_once() {
+        assert_eq!(
+            xccdf12_id("rule", "SV-1r1_rule"),
+            "xccdf_mil.disa.stig_rule_SV-1r1_rule"
+        );
+        assert_eq!(
+            xccdf12_id("benchmark", "xccdf_mil.disa.stig_benchmark_Kubernetes_STIG"),
+            "xccdf_mil.disa.stig_benchmark_Kubernetes_STIG"
+        );
+    }
+
+    #[test]
+    fn exported_results_read_back() {
+        let asset = Asset {
+            host_name: "k8s-cp01".to_string(),
+            ..Default::default()
+        };
+        let xml = checklist_to_xccdf(&asset, &entry("Kubernetes_STIG"), &review());
+        assert!(
+            xml.contains("id=\"xccdf_mil.disa.stig_testresult_Kubernetes_STIG\""),
+            "{xml}"
+        );
+
+        let results = parse_results_reader(xml.as_bytes()).unwrap();
+        assert_eq!(results.benchmark_id, "Kubernetes_STIG");
+        assert_eq!(results.version, "2");
+        assert_eq!(results.target, "k8s-cp01");
+        assert_eq!(results.identity, r"CORP\jdoe");
+        let rules: Vec<(&str, &str)> = results
+            .rule_results
+            .iter()
+            .map(|r| (r.rule_id.as_str(), r.result.as_str()))
+            .collect();
+        assert_eq!(
+            rules,
+            [
+                ("SV-1r1_rule", "fail"),
+                ("SV-2r1_rule", "pass"),
+                ("SV-3r1_rule", "notchecked")
+            ]
+        );
+    }
+}
@@ -1,20 +1,38 @@
 mod api;
+mod cci;
 mod config;
 mod db;
+mod export;
 mod parser;
+mod store;
 mod sync;
+mod taxonomy;
 
 use anyhow::Result;
 use axum::{extract::DefaultBodyLimit, routing::{get, post}, Router};
 use sqlx::PgPool;
-use std::{sync::Arc, time::Duration};
+use std::{
+    sync::{Arc, RwLock},
+    time::Duration,
+};
 use tower_http::cors::{Any, CorsLayer};
 use tracing::info;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
-use api::{catalog::{get_catalog, get_health}, stig::get_stig, upload::{upload_library, upload_stig}};
+use api::{
+    catalog::{get_catalog, get_health, recategorize_catalog},
+    checklist::{
+        export_ckl, export_cklb, export_poam, export_xccdf, get_checklist, get_checklists,
+        import_checklist, import_results,
+    },
+    srg::get_srg_rules,
+    stig::{export_stig_ckl, get_stig},
+    upload::{upload_library, upload_stig},
+};
+use cci::{load_cci_list, CciList};
 use config::{load_sources, Config};
 use db::init_pool;
+use taxonomy::{load_taxonomy, Taxonomy};
 
 /// Unified application state shared by all Axum handlers.
 /// Axum requires a single State type per router.
@@ -22,6 +40,11 @@ use db::init_pool;
 pub struct AppState {
     pub pool: Arc<PgPool>,
     pub config: Arc<Config>,
+    /// Replaced by POST /api/catalog/recategorize when taxonomy.toml changes.
+    /// It is only ever swapped whole, so a poisoned lock still holds a usable
+    /// taxonomy and is recovered with `PoisonError::into_inner`.
+    pub taxonomy: Arc<RwLock<Taxonomy>>,
+    pub cci: Arc<CciList>,
 }
 
 #[tokio::main]
@@ -37,10 +60,13 @@ async fn main() -> Result<()> {
     // Load configuration
     let config = Arc::new(Config::from_env()?);
     let sources = Arc::new(load_sources()?);
+    let taxonomy = Arc::new(RwLock::new(load_taxonomy()?));
+    let cci = Arc::new(load_cci_list(&config.cci_list)?);
 
     info!(
-        "Loaded {} STIG sources, data_dir={}",
+        "Loaded {} STIG sources and {} CCIs, data_dir={}",
         sources.len(),
+        cci.len(),
         config.data_dir.display()
     );
 
@@ -51,6 +77,44 @@ async fn main() -> Result<()> {
     let pool = Arc::new(init_pool(&config.database_url).await?);
     info!("Database connected and migrations applied");
 
+    // Catalog IDs used to include the DISA release marker; fold those entries
+    // into their release-independent ID
+    match store::migrate_release_ids(&pool, &config.data_dir).await {
+        Ok(0) => {}
+        Ok(n) => info!("Migrated {n} catalog entries to release-independent IDs"),
+        Err(e) => tracing::error!("Catalog ID migration failed: {e:#}"),
+    }
+
+    // STIG JSON written by an older parser lacks fields and fixes added since;
+    // re-import it from the packages kept on disk.  Sources in the manifest
+    // are also refreshed by the first sync below
+    match store::reimport_stale_benchmarks(&pool, &config, &taxonomy).await {
+        Ok(reimport) => {
+            if !reimport.reimported.is_empty() {
+                info!(
+                    "Re-imported {} STIGs parsed by an older release: {:?}",
+                    reimport.reimported.len(),
+                    reimport.reimported
+                );
+            }
+            let (synced, manual): (Vec<&String>, Vec<&String>) = reimport
+                .remaining
+                .iter()
+                .partition(|id| sources.iter().any(|s| s.id == **id));
+            if !synced.is_empty() {
+                info!("Refreshed by the next sync: {synced:?}");
+            }
+            if !manual.is_empty() {
+                tracing::warn!(
+                    "{} stored STIGs were parsed by an older release and their packages were \
+                     not kept; re-upload them or the library bundle to refresh: {manual:?}",
+                    manual.len()
+                );
+            }
+        }
+        Err(e) => tracing::error!("Stale STIG re-import failed: {e:#}"),
+    }
+
     // CORS — allow all origins in dev; tighten in production via env or nginx
     let cors = CorsLayer::new()
         .allow_origin(Any)
@@ -60,6 +124,8 @@ async fn main() -> Result<()> {
     let state = AppState {
         pool: pool.clone(),
         config: config.clone(),
+        taxonomy: taxonomy.clone(),
+        cci,
     };
 
     // Build Axum router — single AppState shared by all handlers
@@ -70,9 +136,20 @@ async fn main() -> Result<()> {
     let app = Router::new()
         .route("/api/health", get(get_health))
         .route("/api/catalog", get(get_catalog))
+        .route("/api/catalog/recategorize", post(recategorize_catalog))
         .route("/api/stigs/:id", get(get_stig))
+        .route("/api/stigs/:id/ckl", post(export_stig_ckl))
+        .route("/api/srg/:srg_id", get(get_srg_rules))
         .route("/api/upload", post(upload_stig))
         .route("/api/upload/library", post(upload_library))
+        .route("/api/checklists", get(get_checklists))
+        .route("/api/checklists/import", post(import_checklist))
+        .route("/api/checklists/import/xccdf", post(import_results))
+        .route("/api/checklists/:id", get(get_checklist))
+        .route("/api/checklists/:id/export.ckl", get(export_ckl))
+        .route("/api/checklists/:id/export.cklb", get(export_cklb))
+        .route("/api/checklists/:id/export.xccdf", get(export_xccdf))
+        .route("/api/checklists/:id/poam.xlsx", get(export_poam))
         .with_state(state)
         .layer(DefaultBodyLimit::max(500 * 1024 * 1024))
         .layer(cors);
@@ -82,12 +159,13 @@ async fn main() -> Result<()> {
         let cfg = config.clone();
         let src = sources.clone();
         let db = pool.clone();
+        let tax = taxonomy.clone();
         tokio::spawn(async move {
             let mut interval =
                 tokio::time::interval(Duration::from_secs(cfg.sync_interval_hours * 3600));
             loop {
                 interval.tick().await; // first tick is immediate
-                if let Err(e) = sync::run_sync(&cfg, &src, &db).await {
+                if let Err(e) = sync::run_sync(&cfg, &src, &db, &tax).await {
                     tracing::error!("Sync error: {e:#}");
                 }
             }
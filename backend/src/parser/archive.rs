@@ -0,0 +1,723 @@
+//! Walking STIG packages and library bundles, within limits that stop a
+//! crafted upload from exhausting memory.
+//!
+//! [`walk_archive`] descends through nested ZIPs and classifies every entry
+//! by name; XCCDF documents are parsed as they are decompressed.
+//!
+//! Every byte decompressed from an upload — including the bytes of nested
+//! ZIPs and those decompressed from them — is counted against one
+//! [`ExtractBudget`].  Sizes and ratios are checked against the entry headers
+//! before reading, and again against the bytes actually produced, since the
+//! headers can lie.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::fmt;
+use std::io::{self, BufReader, Cursor, Read, Seek};
+
+use super::{parse_xccdf_reader, StigData};
+
+/// Entries smaller than this are exempt from the compression-ratio check;
+/// tiny, repetitive files legitimately compress far better than XML.
+const RATIO_GRACE_BYTES: u64 = 1024 * 1024;
+
+/// Configurable bounds on what one upload may decompress to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ZipLimits {
+    /// Total uncompressed bytes across every entry of every nested archive.
+    pub max_total_bytes: u64,
+    /// Uncompressed bytes of any single entry.
+    pub max_entry_bytes: u64,
+    /// Uncompressed bytes of a nested ZIP, which is held in memory while it
+    /// is walked.
+    pub max_archive_bytes: u64,
+    /// Uncompressed-to-compressed ratio of any single entry.
+    pub max_ratio: u64,
+    /// Entries across every nested archive.
+    pub max_entries: usize,
+    /// Archives nested below the uploaded one; deeper ones are skipped.
+    pub max_depth: usize,
+}
+
+impl Default for ZipLimits {
+    /// Generous enough for the quarterly SRG/STIG library bundle.
+    fn default() -> Self {
+        Self {
+            max_total_bytes: 8 * 1024 * 1024 * 1024,
+            max_entry_bytes: 1024 * 1024 * 1024,
+            max_archive_bytes: 256 * 1024 * 1024,
+            max_ratio: 100,
+            max_entries: 50_000,
+            max_depth: 4,
+        }
+    }
+}
+
+/// A ZIP that breaks one of the [`ZipLimits`] or names an unsafe path.
+///
+/// Carried inside `anyhow::Error`; callers tell it apart from ordinary
+/// extraction failures with `downcast_ref`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ZipLimitError {
+    TotalSize {
+        limit: u64,
+    },
+    EntrySize {
+        name: String,
+        limit: u64,
+    },
+    ArchiveSize {
+        name: String,
+        limit: u64,
+    },
+    Ratio {
+        name: String,
+        limit: u64,
+    },
+    EntryCount {
+        limit: usize,
+    },
+    /// Absolute path, or one that climbs out of the archive with `..`.
+    UnsafePath {
+        name: String,
+    },
+}
+
+impl ZipLimitError {
+    /// Stable identifier for API responses.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::TotalSize { .. } => "total_size_exceeded",
+            Self::EntrySize { .. } => "entry_size_exceeded",
+            Self::ArchiveSize { .. } => "archive_size_exceeded",
+            Self::Ratio { .. } => "compression_ratio_exceeded",
+            Self::EntryCount { .. } => "entry_count_exceeded",
+            Self::UnsafePath { .. } => "unsafe_path",
+        }
+    }
+
+    /// Whether the whole upload's budget is used up, so the walk must stop.
+    pub fn is_exhausted(&self) -> bool {
+        matches!(self, Self::TotalSize { .. } | Self::EntryCount { .. })
+    }
+}
+
+impl fmt::Display for ZipLimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TotalSize { limit } => {
+                write!(f, "archive expands to more than {limit} bytes")
+            }
+            Self::EntrySize { name, limit } => {
+                write!(f, "entry '{name}' expands to more than {limit} bytes")
+            }
+            Self::ArchiveSize { name, limit } => {
+                write!(
+                    f,
+                    "nested archive '{name}' expands to more than {limit} bytes"
+                )
+            }
+            Self::Ratio { name, limit } => {
+                write!(
+                    f,
+                    "entry '{name}' exceeds the {limit}:1 compression ratio limit"
+                )
+            }
+            Self::EntryCount { limit } => write!(f, "archive has more than {limit} entries"),
+            Self::UnsafePath { name } => write!(f, "entry '{name}' has an unsafe path"),
+        }
+    }
+}
+
+impl std::error::Error for ZipLimitError {}
+
+/// Kind of an extraction error for API responses: the [`ZipLimitError`] kind,
+/// or `fallback` for anything else.
+pub fn error_kind(e: &anyhow::Error, fallback: &'static str) -> &'static str {
+    e.downcast_ref::<ZipLimitError>()
+        .map_or(fallback, ZipLimitError::kind)
+}
+
+/// Running totals for one upload, checked against its [`ZipLimits`].
+struct ExtractBudget {
+    limits: ZipLimits,
+    total_bytes: u64,
+    entries: usize,
+    /// Set when a read is cut off, so the error survives whatever parser was
+    /// consuming the reader.
+    violation: Option<ZipLimitError>,
+}
+
+impl ExtractBudget {
+    fn new(limits: ZipLimits) -> Self {
+        Self {
+            limits,
+            total_bytes: 0,
+            entries: 0,
+            violation: None,
+        }
+    }
+
+    /// Open `reader` as a ZIP, counting its entries against the budget.
+    fn open<R: Read + Seek>(&mut self, reader: R) -> Result<zip::ZipArchive<R>> {
+        let archive = zip::ZipArchive::new(reader)?;
+        self.entries += archive.len();
+        if self.entries > self.limits.max_entries {
+            return Err(ZipLimitError::EntryCount {
+                limit: self.limits.max_entries,
+            }
+            .into());
+        }
+        Ok(archive)
+    }
+
+    /// Check an entry's name and declared sizes before reading it.
+    fn check_entry(&self, file: &zip::read::ZipFile<'_>) -> Result<()> {
+        let name = file.name();
+        if file.enclosed_name().is_none() {
+            return Err(ZipLimitError::UnsafePath {
+                name: name.to_string(),
+            }
+            .into());
+        }
+        if let Some(violation) = self.exceeds(name, file.size(), file.compressed_size()) {
+            return Err(violation.into());
+        }
+        if self.total_bytes.saturating_add(file.size()) > self.limits.max_total_bytes {
+            return Err(ZipLimitError::TotalSize {
+                limit: self.limits.max_total_bytes,
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Wrap an entry so every byte read from it is counted and checked.
+    fn reader<'a, R: Read>(
+        &'a mut self,
+        name: &str,
+        compressed: u64,
+        inner: R,
+    ) -> LimitedReader<'a, R> {
+        LimitedReader {
+            inner,
+            budget: self,
+            name: name.to_string(),
+            compressed,
+            read: 0,
+        }
+    }
+
+    /// Check and read a whole nested archive into memory.
+    fn read_archive(&mut self, mut file: zip::read::ZipFile<'_>) -> Result<Vec<u8>> {
+        self.check_entry(&file)?;
+        let name = file.name().to_string();
+        let limit = self.limits.max_archive_bytes;
+        let too_large = || ZipLimitError::ArchiveSize {
+            name: name.clone(),
+            limit,
+        };
+        if file.size() > limit {
+            return Err(too_large().into());
+        }
+        let compressed = file.compressed_size();
+        let mut bytes = Vec::new();
+        let read = self
+            .reader(&name, compressed, &mut file)
+            .take(limit.saturating_add(1))
+            .read_to_end(&mut bytes);
+        self.take_violation()?;
+        read?;
+        if bytes.len() as u64 > limit {
+            return Err(too_large().into());
+        }
+        Ok(bytes)
+    }
+
+    /// Fail with the limit a [`LimitedReader`] hit, if any.
+    fn take_violation(&mut self) -> Result<()> {
+        match self.violation.take() {
+            Some(violation) => Err(violation.into()),
+            None => Ok(()),
+        }
+    }
+
+    fn exceeds(&self, name: &str, size: u64, compressed: u64) -> Option<ZipLimitError> {
+        let limits = &self.limits;
+        if size > limits.max_entry_bytes {
+            return Some(ZipLimitError::EntrySize {
+                name: name.to_string(),
+                limit: limits.max_entry_bytes,
+            });
+        }
+        if size > RATIO_GRACE_BYTES && size / compressed.max(1) > limits.max_ratio {
+            return Some(ZipLimitError::Ratio {
+                name: name.to_string(),
+                limit: limits.max_ratio,
+            });
+        }
+        None
+    }
+}
+
+/// An entry reader that fails once the entry or the upload breaks a limit.
+struct LimitedReader<'a, R> {
+    inner: R,
+    budget: &'a mut ExtractBudget,
+    name: String,
+    compressed: u64,
+    read: u64,
+}
+
+impl<R: Read> Read for LimitedReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.read += n as u64;
+        self.budget.total_bytes += n as u64;
+
+        let violation = self
+            .budget
+            .exceeds(&self.name, self.read, self.compressed)
+            .or_else(|| {
+                (self.budget.total_bytes > self.budget.limits.max_total_bytes).then_some(
+                    ZipLimitError::TotalSize {
+                        limit: self.budget.limits.max_total_bytes,
+                    },
+                )
+            });
+        if let Some(violation) = violation {
+            let message = violation.to_string();
+            self.budget.violation = Some(violation);
+            return Err(io::Error::other(message));
+        }
+        Ok(n)
+    }
+}
+
+// ── Walking ──────────────────────────────────────────────────────────────────
+
+/// What an archive entry holds, judged by its name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryKind {
+    /// Manual STIG XCCDF, e.g. `U_RHEL_9_STIG_V2R2_Manual-xccdf.xml`.
+    ManualXccdf,
+    /// SCAP benchmark: a `*_Benchmark.xml` data stream or `*Benchmark-xccdf.xml`.
+    ScapBenchmark,
+    /// Security Requirements Guide, as an XCCDF or a ZIP of one.
+    Srg,
+    /// `*_Overview.pdf`.
+    Overview,
+    /// A nested ZIP.
+    Archive,
+    Other,
+}
+
+/// Classify an entry by its name within its archive.
+pub fn classify_entry(name: &str) -> EntryKind {
+    let base = name.rsplit('/').next().unwrap_or(name).to_lowercase();
+    let tokens: Vec<&str> = base.split(|c: char| !c.is_ascii_alphanumeric()).collect();
+    let srg = tokens.contains(&"srg") && !tokens.contains(&"stig");
+
+    if base.ends_with("_xccdf.xml")
+        || base.ends_with("-xccdf.xml")
+        || base.ends_with("_benchmark.xml")
+    {
+        if base.contains("benchmark") {
+            EntryKind::ScapBenchmark
+        } else if srg {
+            EntryKind::Srg
+        } else {
+            EntryKind::ManualXccdf
+        }
+    } else if base.ends_with(".zip") {
+        if srg {
+            EntryKind::Srg
+        } else {
+            EntryKind::Archive
+        }
+    } else if base.ends_with(".pdf") && tokens.contains(&"overview") {
+        EntryKind::Overview
+    } else {
+        EntryKind::Other
+    }
+}
+
+/// An entry found while walking an upload.
+#[derive(Debug, Clone)]
+pub struct ArchiveEntry {
+    /// Names of the enclosing archives below the upload, outermost first,
+    /// then the entry's own name.
+    pub path: Vec<String>,
+    pub kind: EntryKind,
+}
+
+impl ArchiveEntry {
+    /// Name within the archive that holds it.
+    pub fn name(&self) -> &str {
+        self.path.last().map_or("", String::as_str)
+    }
+
+    /// `U_Cisco_IOS_XE_Router_STIG.zip!/U_…_RTR_STIG_V3R2_Manual-xccdf.xml`
+    pub fn display_path(&self) -> String {
+        self.path.join("!/")
+    }
+
+    fn is_zip(&self) -> bool {
+        self.name().to_lowercase().ends_with(".zip")
+    }
+}
+
+/// One entry of a walk and what became of it.
+pub enum Walked {
+    /// An XCCDF document, parsed as it was decompressed.
+    Xccdf {
+        entry: ArchiveEntry,
+        stig: Box<Result<StigData>>,
+    },
+    /// Not a benchmark, too deeply nested, or unreadable.
+    Skipped { entry: ArchiveEntry, reason: String },
+    /// Broke one of the [`ZipLimits`]; nothing of it was kept.
+    Rejected {
+        entry: ArchiveEntry,
+        error: ZipLimitError,
+    },
+}
+
+/// Walk a ZIP and every ZIP nested in it, up to `limits.max_depth` levels,
+/// parsing each XCCDF and reporting everything else it passed over.
+///
+/// SRGs are parsed like manual STIGs when `include_srg` is set, and skipped
+/// otherwise.  An entry that breaks a per-entry limit is rejected and the
+/// walk goes on; exhausting the total size or entry count ends it, with the
+/// rejection as its last item.  Only an unreadable upload is an error.
+pub fn walk_archive<R: Read + Seek>(
+    zip: R,
+    limits: ZipLimits,
+    include_srg: bool,
+) -> Result<Vec<Walked>> {
+    let mut budget = ExtractBudget::new(limits);
+    let mut archive = budget.open(zip)?;
+    let mut walked = Vec::new();
+    walk_entries(&mut archive, &[], &mut budget, include_srg, &mut walked);
+    Ok(walked)
+}
+
+/// Walk one (possibly nested) archive; false once the walk must stop.
+fn walk_entries<R: Read + Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    parents: &[String],
+    budget: &mut ExtractBudget,
+    include_srg: bool,
+    walked: &mut Vec<Walked>,
+) -> bool {
+    for i in 0..archive.len() {
+        let file = match archive.by_index(i) {
+            Ok(f) => f,
+            Err(e) => {
+                let mut path = parents.to_vec();
+                path.push(format!("entry #{i}"));
+                let entry = ArchiveEntry {
+                    path,
+                    kind: EntryKind::Other,
+                };
+                walked.push(Walked::Skipped {
+                    entry,
+                    reason: format!("unreadable entry: {e}"),
+                });
+                continue;
+            }
+        };
+        if file.is_dir() {
+            continue;
+        }
+        let mut path = parents.to_vec();
+        path.push(file.name().to_string());
+        let entry = ArchiveEntry {
+            kind: classify_entry(file.name()),
+            path,
+        };
+
+        let skip = match entry.kind {
+            EntryKind::Srg if !include_srg => {
+                Some("Security Requirements Guide, not a STIG".to_string())
+            }
+            EntryKind::Overview => Some("overview document".to_string()),
+            EntryKind::Other => Some("not an XCCDF document or ZIP archive".to_string()),
+            _ if entry.is_zip() && parents.len() >= budget.limits.max_depth => Some(format!(
+                "nested more than {} archives deep",
+                budget.limits.max_depth
+            )),
+            _ => None,
+        };
+        if let Some(reason) = skip {
+            walked.push(Walked::Skipped { entry, reason });
+            continue;
+        }
+
+        if !entry.is_zip() {
+            if let Err(e) = budget.check_entry(&file) {
+                if !record_failure(walked, entry, e, "unreadable entry") {
+                    return false;
+                }
+                continue;
+            }
+            let compressed = file.compressed_size();
+            let stig = parse_xccdf_reader(BufReader::new(budget.reader(
+                entry.name(),
+                compressed,
+                file,
+            )));
+            // A limit hit mid-parse surfaces as an XML error; report the limit
+            match budget.take_violation() {
+                Ok(()) => walked.push(Walked::Xccdf {
+                    entry,
+                    stig: Box::new(stig),
+                }),
+                Err(e) => {
+                    if !record_failure(walked, entry, e, "unreadable entry") {
+                        return false;
+                    }
+                }
+            }
+            continue;
+        }
+
+        // Nested ZIP — ZipArchive needs Seek, so the inner ZIP itself is buffered
+        let opened = budget
+            .read_archive(file)
+            .and_then(|bytes| budget.open(Cursor::new(bytes)));
+        let mut inner = match opened {
+            Ok(inner) => inner,
+            Err(e) => {
+                if !record_failure(walked, entry, e, "not a readable ZIP archive") {
+                    return false;
+                }
+                continue;
+            }
+        };
+        if !walk_entries(&mut inner, &entry.path, budget, include_srg, walked) {
+            return false;
+        }
+    }
+    true
+}
+
+/// Record why an entry could not be read; false when that ends the walk.
+fn record_failure(
+    walked: &mut Vec<Walked>,
+    entry: ArchiveEntry,
+    e: anyhow::Error,
+    what: &str,
+) -> bool {
+    match e.downcast::<ZipLimitError>() {
+        Ok(error) => {
+            let go_on = !error.is_exhausted();
+            walked.push(Walked::Rejected { entry, error });
+            go_on
+        }
+        Err(e) => {
+            walked.push(Walked::Skipped {
+                entry,
+                reason: format!("{what}: {e}"),
+            });
+            true
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+    use zip::CompressionMethod;
+
+    const XCCDF: &[u8] = br#"<Benchmark xmlns="http://checklists.nist.gov/xccdf/1.1" id="A_STIG">
+  <title>A STIG</title><version>1</version>
+</Benchmark>"#;
+
+    /// A deflated ZIP of `entries`, in order.
+    fn zip_of(entries: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        for (name, bytes) in entries {
+            writer.start_file(*name, options).unwrap();
+            writer.write_all(bytes).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    /// Rewrite the uncompressed size of a one-entry ZIP's headers.
+    fn declare_size(zip: &mut [u8], size: u32) {
+        let find = |signature: &[u8]| zip.windows(4).position(|w| w == signature).unwrap();
+        let (local, central) = (find(b"PK\x03\x04"), find(b"PK\x01\x02"));
+        zip[local + 22..local + 26].copy_from_slice(&size.to_le_bytes());
+        zip[central + 24..central + 28].copy_from_slice(&size.to_le_bytes());
+    }
+
+    fn walk(zip: Vec<u8>, limits: ZipLimits) -> Result<Vec<Walked>> {
+        walk_archive(Cursor::new(zip), limits, false)
+    }
+
+    /// Kind of the walk's last rejection.
+    fn rejected(walked: &[Walked]) -> &'static str {
+        walked
+            .iter()
+            .rev()
+            .find_map(|w| match w {
+                Walked::Rejected { error, .. } => Some(error.kind()),
+                _ => None,
+            })
+            .expect("no entry was rejected")
+    }
+
+    #[test]
+    fn classifies_entries_by_name() {
+        let cases = [
+            (
+                "U_RHEL_9_STIG_V2R2_Manual-xccdf.xml",
+                EntryKind::ManualXccdf,
+            ),
+            (
+                "a/b/U_Kubernetes_STIG_V2R5_Manual-xccdf.xml",
+                EntryKind::ManualXccdf,
+            ),
+            (
+                "U_MS_Windows_11_V2R3_STIG_SCAP_1-3_Benchmark.xml",
+                EntryKind::ScapBenchmark,
+            ),
+            (
+                "U_Cisco_IOS_XE_NDM_V3R4_STIG_SCAP_1-3_Benchmark-xccdf.xml",
+                EntryKind::ScapBenchmark,
+            ),
+            (
+                "U_Application_Server_SRG_V4R1_Manual-xccdf.xml",
+                EntryKind::Srg,
+            ),
+            ("U_Network_Device_Management_SRG_V5R3.zip", EntryKind::Srg),
+            ("U_RHEL_9_V2R2_STIG.zip", EntryKind::Archive),
+            ("U_RHEL_9_V2R2_Overview.pdf", EntryKind::Overview),
+            ("U_RHEL_9_V2R2_Revision_History.pdf", EntryKind::Other),
+            ("README.txt", EntryKind::Other),
+        ];
+        for (name, kind) in cases {
+            assert_eq!(classify_entry(name), kind, "{name}");
+        }
+    }
+
+    #[test]
+    fn walks_nested_archives_within_limits() {
+        let inner = zip_of(&[("U_A_STIG_V1R1_Manual-xccdf.xml", XCCDF)]);
+        let outer = zip_of(&[("U_A_V1R1_STIG.zip", &inner), ("readme.txt", b"hello")]);
+        let walked = walk(outer, ZipLimits::default()).unwrap();
+        assert_eq!(walked.len(), 2);
+        match &walked[0] {
+            Walked::Xccdf { entry, stig } => {
+                assert_eq!(
+                    entry.display_path(),
+                    "U_A_V1R1_STIG.zip!/U_A_STIG_V1R1_Manual-xccdf.xml"
+                );
+                assert_eq!(stig.as_ref().as_ref().unwrap().benchmark_id, "A_STIG");
+            }
+            _ => panic!("expected the XCCDF first"),
+        }
+        assert!(matches!(
+            &walked[1],
+            Walked::Skipped { entry, .. } if entry.name() == "readme.txt"
+        ));
+    }
+
+    #[test]
+    fn rejects_compression_ratio() {
+        let zip = zip_of(&[(
+            "U_A_STIG_V1R1_Manual-xccdf.xml",
+            &vec![b' '; 4 * 1024 * 1024],
+        )]);
+        let walked = walk(zip.clone(), ZipLimits::default()).unwrap();
+        assert_eq!(rejected(&walked), "compression_ratio_exceeded");
+
+        // Uploads report the rejection by the same kind
+        let err = crate::parser::extract_xccdf_from_zip(&zip, ZipLimits::default())
+            .err()
+            .expect("upload should be rejected");
+        assert_eq!(
+            error_kind(&err, "extraction_failed"),
+            "compression_ratio_exceeded"
+        );
+    }
+
+    #[test]
+    fn rejects_total_size_and_stops() {
+        let zip = zip_of(&[
+            ("U_A_STIG_V1R1_Manual-xccdf.xml", &[b'a'; 600][..]),
+            ("U_B_STIG_V1R1_Manual-xccdf.xml", &[b'b'; 600][..]),
+            ("U_C_STIG_V1R1_Manual-xccdf.xml", &[b'c'; 600][..]),
+        ]);
+        let limits = ZipLimits {
+            max_total_bytes: 1000,
+            ..Default::default()
+        };
+        let walked = walk(zip, limits).unwrap();
+        assert_eq!(walked.len(), 2);
+        assert_eq!(rejected(&walked), "total_size_exceeded");
+    }
+
+    #[test]
+    fn rejects_entry_count() {
+        let zip = zip_of(&[("a.txt", b"a"), ("b.txt", b"b")]);
+        let limits = ZipLimits {
+            max_entries: 1,
+            ..Default::default()
+        };
+        let err = walk(zip, limits).err().expect("walk should fail");
+        assert_eq!(
+            error_kind(&err, "extraction_failed"),
+            "entry_count_exceeded"
+        );
+    }
+
+    #[test]
+    fn rejects_unsafe_paths() {
+        for name in [
+            "../U_A_STIG_V1R1_Manual-xccdf.xml",
+            "/etc/U_A_STIG_V1R1_Manual-xccdf.xml",
+        ] {
+            let walked = walk(zip_of(&[(name, XCCDF)]), ZipLimits::default()).unwrap();
+            assert_eq!(rejected(&walked), "unsafe_path", "{name}");
+        }
+    }
+
+    #[test]
+    fn counts_bytes_read_when_headers_lie() {
+        let mut zip = zip_of(&[("U_A_STIG_V1R1_Manual-xccdf.xml", &[b'a'; 4000][..])]);
+        declare_size(&mut zip, 10);
+        let limits = ZipLimits {
+            max_entry_bytes: 1000,
+            ..Default::default()
+        };
+        let walked = walk(zip, limits).unwrap();
+        assert_eq!(rejected(&walked), "entry_size_exceeded");
+    }
+
+    #[test]
+    fn rejects_nested_archives_too_large_to_buffer() {
+        let inner = zip_of(&[
+            ("U_A_STIG_V1R1_Manual-xccdf.xml", XCCDF),
+            ("pad.bin", &[7; 2000][..]),
+        ]);
+        let outer = zip_of(&[("U_A_V1R1_STIG.zip", &inner)]);
+        let limits = ZipLimits {
+            max_archive_bytes: 100,
+            ..Default::default()
+        };
+        let walked = walk(outer, limits).unwrap();
+        assert_eq!(rejected(&walked), "archive_size_exceeded");
+
+        // The same archive whose header claims it is small is cut off as it is read
+        let mut lying = zip_of(&[("U_A_V1R1_STIG.zip", &inner)]);
+        declare_size(&mut lying, 10);
+        let walked = walk(lying, limits).unwrap();
+        assert_eq!(rejected(&walked), "archive_size_exceeded");
+    }
+}
@@ -0,0 +1,499 @@
+//! STIG Viewer 2 `.ckl` checklists, as also written by Evaluate-STIG.
+//!
+//! A checklist is one asset reviewed against one or more STIGs (`<iSTIG>`).
+//! Each STIG is read into the same `StigData`/`Rule` model the XCCDF parser
+//! produces, with the review results filled in on every rule.
+
+use anyhow::{bail, Result};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
+use std::io::BufRead;
+
+use super::{
+    map_severity, normalize_block, BenchmarkReference, CheckContentRef, Reference, Rule, StigData,
+    TextBlock,
+};
+
+/// A reviewed checklist — the shape stored under `DATA_DIR/checklists`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Checklist {
+    pub asset: Asset,
+    pub stigs: Vec<ChecklistStig>,
+    /// Who reviewed the asset and when; CKL and CKLB do not record this.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub review: Option<Review>,
+}
+
+/// The reviewer of a checklist and the time span of the review, written to
+/// an XCCDF `TestResult` as `<identity>`, `start-time` and `end-time`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Review {
+    pub identity: String,
+    /// `xsd:dateTime`, e.g. `2026-10-01T10:00:00`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_time: Option<String>,
+}
+
+/// The `<ASSET>` block: the host or application under review.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Asset {
+    /// `None`, `Workstation`, `Member Server` or `Domain Controller`.
+    pub role: String,
+    /// `Computing` or `Non-Computing`.
+    pub asset_type: String,
+    pub marking: String,
+    pub host_name: String,
+    pub host_ip: String,
+    pub host_mac: String,
+    pub host_fqdn: String,
+    pub target_comment: String,
+    pub tech_area: String,
+    pub target_key: String,
+    pub web_or_database: bool,
+    pub web_db_site: String,
+    pub web_db_instance: String,
+}
+
+/// One `<iSTIG>`: a benchmark and the review results for its rules.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChecklistStig {
+    /// Catalog entry matched on import by benchmark id and version, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub catalog_id: Option<String>,
+    /// STIG_INFO `uuid`, kept so a re-export keeps the same identity.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<String>,
+    /// STIG_INFO `classification`, e.g. `UNCLASSIFIED`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub classification: Option<String>,
+    /// STIG_INFO `filename` of the XCCDF the checklist was created from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filename: Option<String>,
+    pub stig: StigData,
+}
+
+/// Map a CKL `<STATUS>` onto the frontend's status values.
+pub fn status_from_ckl(status: &str) -> &'static str {
+    match status {
+        "NotAFinding" | "Not_A_Finding" => "not_a_finding",
+        "Open" => "open",
+        "Not_Applicable" | "NotApplicable" => "not_applicable",
+        _ => "not_reviewed",
+    }
+}
+
+/// Map a frontend status value onto a CKL `<STATUS>`.
+pub fn status_to_ckl(status: &str) -> &'static str {
+    match status {
+        "not_a_finding" => "NotAFinding",
+        "open" => "Open",
+        "not_applicable" => "Not_Applicable",
+        _ => "Not_Reviewed",
+    }
+}
+
+/// Normalise a status given either as a frontend value (`not_a_finding`) or
+/// as a CKL `<STATUS>` (`NotAFinding`); `None` when it is neither.
+pub fn normalize_status(status: &str) -> Option<&'static str> {
+    match status {
+        "not_a_finding" => Some("not_a_finding"),
+        "open" => Some("open"),
+        "not_applicable" => Some("not_applicable"),
+        "not_reviewed" | "Not_Reviewed" => Some("not_reviewed"),
+        _ => Some(status_from_ckl(status)).filter(|s| *s != "not_reviewed"),
+    }
+}
+
+/// Review data and `STIG_DATA` attributes of the `<VULN>` being read.
+#[derive(Default)]
+struct Vuln {
+    attrs: Vec<(String, String)>,
+    status: String,
+    finding_details: String,
+    comments: String,
+    severity_override: String,
+    severity_justification: String,
+}
+
+/// Parse a `.ckl` document.
+pub fn parse_ckl_reader<R: BufRead>(xml: R) -> Result<Checklist> {
+    let mut reader = Reader::from_reader(super::encoding::utf8_xml(xml)?);
+    reader.config_mut().trim_text(true);
+
+    let mut seen_checklist = false;
+    let mut asset = Asset::default();
+    let mut stigs: Vec<ChecklistStig> = Vec::new();
+    let mut current_stig: Option<ChecklistStig> = None;
+    let mut current_vuln: Option<Vuln> = None;
+    // Name/value pair of the SI_DATA or STIG_DATA being read
+    let mut pair = (String::new(), String::new());
+    let mut path: Vec<String> = Vec::new();
+
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(ref e)) => {
+                let local = String::from_utf8_lossy(e.local_name().as_ref()).to_string();
+                match local.as_str() {
+                    "CHECKLIST" => seen_checklist = true,
+                    "iSTIG" => current_stig = Some(ChecklistStig::default()),
+                    "VULN" => current_vuln = Some(Vuln::default()),
+                    "SI_DATA" | "STIG_DATA" => pair = (String::new(), String::new()),
+                    _ => {}
+                }
+                path.push(local);
+            }
+            Ok(Event::End(ref e)) => {
+                let local = String::from_utf8_lossy(e.local_name().as_ref()).to_string();
+                match local.as_str() {
+                    "SI_DATA" => {
+                        if let Some(stig) = current_stig.as_mut() {
+                            apply_stig_info(stig, &pair.0, std::mem::take(&mut pair.1));
+                        }
+                    }
+                    "STIG_DATA" => {
+                        if let Some(vuln) = current_vuln.as_mut() {
+                            vuln.attrs.push(std::mem::take(&mut pair));
+                        }
+                    }
+                    "VULN" => {
+                        if let (Some(vuln), Some(stig)) =
+                            (current_vuln.take(), current_stig.as_mut())
+                        {
+                            stig.stig.rules.push(vuln_to_rule(vuln));
+                        }
+                    }
+                    "iSTIG" => {
+                        if let Some(mut stig) = current_stig.take() {
+                            if stig.stig.title.is_empty() {
+                                stig.stig.title = "Imported Checklist".to_string();
+                            }
+                            stigs.push(stig);
+                        }
+                    }
+                    _ => {}
+                }
+                path.pop();
+            }
+            Ok(Event::Text(ref e)) => {
+                let text = e.unescape().unwrap_or_default().to_string();
+                if text.is_empty() {
+                    continue;
+                }
+                let (parent, tag) = match path.as_slice() {
+                    [.., parent, tag] => (parent.as_str(), tag.as_str()),
+                    _ => continue,
+                };
+                match (parent, tag) {
+                    ("ASSET", _) => apply_asset_field(&mut asset, tag, text),
+                    ("SI_DATA", "SID_NAME") | ("STIG_DATA", "VULN_ATTRIBUTE") => {
+                        pair.0.push_str(&text)
+                    }
+                    ("SI_DATA", "SID_DATA") | ("STIG_DATA", "ATTRIBUTE_DATA") => {
+                        pair.1.push_str(&text)
+                    }
+                    ("VULN", _) => {
+                        if let Some(vuln) = current_vuln.as_mut() {
+                            let slot = match tag {
+                                "STATUS" => Some(&mut vuln.status),
+                                "FINDING_DETAILS" => Some(&mut vuln.finding_details),
+                                "COMMENTS" => Some(&mut vuln.comments),
+                                "SEVERITY_OVERRIDE" => Some(&mut vuln.severity_override),
+                                "SEVERITY_JUSTIFICATION" => Some(&mut vuln.severity_justification),
+                                _ => None,
+                            };
+                            if let Some(slot) = slot {
+                                slot.push_str(&text);
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => bail!("XML parse error: {e}"),
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    if !seen_checklist {
+        bail!("No CHECKLIST element found in CKL");
+    }
+    if stigs.is_empty() {
+        bail!("CKL contains no iSTIG");
+    }
+
+    Ok(Checklist {
+        asset,
+        stigs,
+        review: None,
+    })
+}
+
+fn apply_asset_field(asset: &mut Asset, tag: &str, text: String) {
+    match tag {
+        "ROLE" => asset.role = text,
+        "ASSET_TYPE" => asset.asset_type = text,
+        "MARKING" => asset.marking = text,
+        "HOST_NAME" => asset.host_name = text,
+        "HOST_IP" => asset.host_ip = text,
+        "HOST_MAC" => asset.host_mac = text,
+        "HOST_FQDN" => asset.host_fqdn = text,
+        "TARGET_COMMENT" => asset.target_comment = text,
+        "TECH_AREA" => asset.tech_area = text,
+        "TARGET_KEY" => asset.target_key = text,
+        "WEB_OR_DATABASE" => asset.web_or_database = text.eq_ignore_ascii_case("true"),
+        "WEB_DB_SITE" => asset.web_db_site = text,
+        "WEB_DB_INSTANCE" => asset.web_db_instance = text,
+        _ => {}
+    }
+}
+
+/// Copy one `STIG_INFO` `SI_DATA` entry onto the checklist STIG.
+fn apply_stig_info(stig: &mut ChecklistStig, name: &str, value: String) {
+    if value.is_empty() {
+        return;
+    }
+    match name {
+        "version" => stig.stig.version = value,
+        "stigid" => stig.stig.benchmark_id = value,
+        "title" => stig.stig.title = value,
+        "description" => stig.stig.description = value,
+        "releaseinfo" => stig.stig.release_info = value,
+        "notice" => stig.stig.notices.push(TextBlock {
+            id: value,
+            text: String::new(),
+        }),
+        "source" => stig.stig.references.push(BenchmarkReference {
+            source: value,
+            ..Default::default()
+        }),
+        "uuid" => stig.uuid = Some(value),
+        "classification" => stig.classification = Some(value),
+        "filename" => stig.filename = Some(value),
+        _ => {}
+    }
+}
+
+/// Build a rule from a `<VULN>`'s `STIG_DATA` attributes and review data.
+fn vuln_to_rule(vuln: Vuln) -> Rule {
+    let mut rule = Rule {
+        status: status_from_ckl(&vuln.status).to_string(),
+        finding_details: vuln.finding_details,
+        comments: vuln.comments,
+        severity_override: (!vuln.severity_override.is_empty())
+            .then(|| map_severity(&vuln.severity_override).to_string()),
+        severity_justification: (!vuln.severity_justification.is_empty())
+            .then_some(vuln.severity_justification),
+        severity: map_severity("medium").to_string(),
+        ..Default::default()
+    };
+
+    for (name, value) in vuln.attrs {
+        if value.is_empty() {
+            continue;
+        }
+        match name.as_str() {
+            "Vuln_Num" => rule.group_id = value,
+            "Severity" => rule.severity = map_severity(&value).to_string(),
+            "Group_Title" => {
+                rule.srg_id = value.starts_with("SRG-").then(|| value.clone());
+                rule.group_title = value;
+            }
+            "Rule_ID" => rule.id = value,
+            "Rule_Ver" => rule.stig_id = value,
+            "Rule_Title" => rule.title = value,
+            "Vuln_Discuss" => rule.description = normalize_block(&value),
+            "IA_Controls" => rule.ia_controls = Some(value),
+            "Check_Content" => rule.check_text = normalize_block(&value),
+            "Fix_Text" => rule.fix_text = normalize_block(&value),
+            "False_Positives" => rule.false_positives = Some(value),
+            "False_Negatives" => rule.false_negatives = Some(value),
+            "Documentable" => rule.documentable = Some(value),
+            "Mitigations" => rule.mitigations = Some(value),
+            "Potential_Impact" => rule.potential_impacts = Some(value),
+            "Third_Party_Tools" => rule.third_party_tools = Some(value),
+            "Mitigation_Control" => rule.mitigation_control = Some(value),
+            "Responsibility" => rule.responsibility = Some(value),
+            "Security_Override_Guidance" => rule.severity_override_guidance = Some(value),
+            "Check_Content_Ref" => {
+                rule.check_content_ref = Some(CheckContentRef {
+                    href: String::new(),
+                    name: value,
+                })
+            }
+            "Weight" => rule.weight = Some(value),
+            "TargetKey" => rule.references.push(Reference {
+                publisher: "DISA".to_string(),
+                ref_type: "DPMS Target".to_string(),
+                identifier: value,
+                ..Default::default()
+            }),
+            "LEGACY_ID" => rule.legacy_ids.push(value),
+            "CCI_REF" => rule.cci_ids.push(value),
+            _ => {}
+        }
+    }
+
+    // Older checklists omit Rule_Ver; fall back to the V-number as the XCCDF parser does
+    if rule.stig_id.is_empty() {
+        rule.stig_id = rule.group_id.clone();
+    }
+    rule
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CKL: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!--DISA STIG Viewer :: 2.17-->
+<CHECKLIST>
+  <ASSET>
+    <ROLE>Member Server</ROLE>
+    <ASSET_TYPE>Computing</ASSET_TYPE>
+    <HOST_NAME>k8s-cp01</HOST_NAME>
+    <HOST_IP>10.0.0.5</HOST_IP>
+    <TARGET_KEY>5289</TARGET_KEY>
+    <WEB_OR_DATABASE>true</WEB_OR_DATABASE>
+  </ASSET>
+  <STIGS>
+    <iSTIG>
+      <STIG_INFO>
+        <SI_DATA><SID_NAME>version</SID_NAME><SID_DATA>2</SID_DATA></SI_DATA>
+        <SI_DATA><SID_NAME>customname</SID_NAME></SI_DATA>
+        <SI_DATA><SID_NAME>stigid</SID_NAME><SID_DATA>Kubernetes_STIG</SID_DATA></SI_DATA>
+        <SI_DATA><SID_NAME>releaseinfo</SID_NAME><SID_DATA>Release: 5 Benchmark Date: 05 Jan 2026</SID_DATA></SI_DATA>
+        <SI_DATA><SID_NAME>title</SID_NAME><SID_DATA>Kubernetes Security Technical Implementation Guide</SID_DATA></SI_DATA>
+        <SI_DATA><SID_NAME>uuid</SID_NAME><SID_DATA>3b1a2c4d-0000-4000-8000-000000000001</SID_DATA></SI_DATA>
+      </STIG_INFO>
+      <VULN>
+        <STIG_DATA><VULN_ATTRIBUTE>Vuln_Num</VULN_ATTRIBUTE><ATTRIBUTE_DATA>V-242376</ATTRIBUTE_DATA></STIG_DATA>
+        <STIG_DATA><VULN_ATTRIBUTE>Severity</VULN_ATTRIBUTE><ATTRIBUTE_DATA>high</ATTRIBUTE_DATA></STIG_DATA>
+        <STIG_DATA><VULN_ATTRIBUTE>Group_Title</VULN_ATTRIBUTE><ATTRIBUTE_DATA>SRG-APP-000014-CTR-000035</ATTRIBUTE_DATA></STIG_DATA>
+        <STIG_DATA><VULN_ATTRIBUTE>Rule_ID</VULN_ATTRIBUTE><ATTRIBUTE_DATA>SV-242376r960759_rule</ATTRIBUTE_DATA></STIG_DATA>
+        <STIG_DATA><VULN_ATTRIBUTE>Rule_Ver</VULN_ATTRIBUTE><ATTRIBUTE_DATA>CNTR-K8-000150</ATTRIBUTE_DATA></STIG_DATA>
+        <STIG_DATA><VULN_ATTRIBUTE>Rule_Title</VULN_ATTRIBUTE><ATTRIBUTE_DATA>Use TLS 1.2 &amp; later.</ATTRIBUTE_DATA></STIG_DATA>
+        <STIG_DATA><VULN_ATTRIBUTE>Check_Content</VULN_ATTRIBUTE><ATTRIBUTE_DATA>Run the command:
+
+grep -i tls-min-version *</ATTRIBUTE_DATA></STIG_DATA>
+        <STIG_DATA><VULN_ATTRIBUTE>CCI_REF</VULN_ATTRIBUTE><ATTRIBUTE_DATA>CCI-000068</ATTRIBUTE_DATA></STIG_DATA>
+        <STIG_DATA><VULN_ATTRIBUTE>CCI_REF</VULN_ATTRIBUTE><ATTRIBUTE_DATA>CCI-001453</ATTRIBUTE_DATA></STIG_DATA>
+        <STATUS>Open</STATUS>
+        <FINDING_DETAILS>tls-min-version is not set</FINDING_DETAILS>
+        <COMMENTS></COMMENTS>
+        <SEVERITY_OVERRIDE>medium</SEVERITY_OVERRIDE>
+        <SEVERITY_JUSTIFICATION>Isolated network</SEVERITY_JUSTIFICATION>
+      </VULN>
+      <VULN>
+        <STIG_DATA><VULN_ATTRIBUTE>Vuln_Num</VULN_ATTRIBUTE><ATTRIBUTE_DATA>V-242377</ATTRIBUTE_DATA></STIG_DATA>
+        <STATUS>NotAFinding</STATUS>
+      </VULN>
+    </iSTIG>
+  </STIGS>
+</CHECKLIST>"#;
+
+    #[test]
+    fn status_from_ckl_maps_every_ckl_status() {
+        assert_eq!(status_from_ckl("NotAFinding"), "not_a_finding");
+        assert_eq!(status_from_ckl("Not_A_Finding"), "not_a_finding");
+        assert_eq!(status_from_ckl("Open"), "open");
+        assert_eq!(status_from_ckl("Not_Applicable"), "not_applicable");
+        assert_eq!(status_from_ckl("NotApplicable"), "not_applicable");
+        assert_eq!(status_from_ckl("Not_Reviewed"), "not_reviewed");
+        assert_eq!(status_from_ckl(""), "not_reviewed");
+    }
+
+    #[test]
+    fn status_to_ckl_round_trips() {
+        for status in ["not_a_finding", "open", "not_applicable", "not_reviewed"] {
+            assert_eq!(status_from_ckl(status_to_ckl(status)), status);
+        }
+        assert_eq!(status_to_ckl("bogus"), "Not_Reviewed");
+    }
+
+    #[test]
+    fn normalize_status_accepts_frontend_and_ckl_spellings() {
+        assert_eq!(normalize_status("open"), Some("open"));
+        assert_eq!(normalize_status("Open"), Some("open"));
+        assert_eq!(normalize_status("NotAFinding"), Some("not_a_finding"));
+        assert_eq!(normalize_status("not_applicable"), Some("not_applicable"));
+        assert_eq!(normalize_status("Not_Applicable"), Some("not_applicable"));
+        assert_eq!(normalize_status("Not_Reviewed"), Some("not_reviewed"));
+        assert_eq!(normalize_status("not_reviewed"), Some("not_reviewed"));
+        assert_eq!(normalize_status("OPEN"), None);
+        assert_eq!(normalize_status("pass"), None);
+        assert_eq!(normalize_status(""), None);
+    }
+
+    #[test]
+    fn parses_asset_and_stig_info() {
+        let checklist = parse_ckl_reader(CKL.as_bytes()).unwrap();
+        assert_eq!(checklist.asset.role, "Member Server");
+        assert_eq!(checklist.asset.host_name, "k8s-cp01");
+        assert_eq!(checklist.asset.host_ip, "10.0.0.5");
+        assert_eq!(checklist.asset.target_key, "5289");
+        assert!(checklist.asset.web_or_database);
+
+        let [stig] = &checklist.stigs[..] else {
+            panic!("expected one iSTIG")
+        };
+        assert_eq!(stig.stig.benchmark_id, "Kubernetes_STIG");
+        assert_eq!(stig.stig.version, "2");
+        assert_eq!(
+            stig.stig.release_info,
+            "Release: 5 Benchmark Date: 05 Jan 2026"
+        );
+        assert_eq!(
+            stig.uuid.as_deref(),
+            Some("3b1a2c4d-0000-4000-8000-000000000001")
+        );
+    }
+
+    #[test]
+    fn parses_vuln_attributes_and_review() {
+        let checklist = parse_ckl_reader(CKL.as_bytes()).unwrap();
+        let rules = &checklist.stigs[0].stig.rules;
+        assert_eq!(rules.len(), 2);
+
+        let rule = &rules[0];
+        assert_eq!(rule.id, "SV-242376r960759_rule");
+        assert_eq!(rule.stig_id, "CNTR-K8-000150");
+        assert_eq!(rule.group_id, "V-242376");
+        assert_eq!(rule.srg_id.as_deref(), Some("SRG-APP-000014-CTR-000035"));
+        assert_eq!(rule.title, "Use TLS 1.2 & later.");
+        assert_eq!(rule.severity, "CAT I");
+        assert_eq!(
+            rule.check_text,
+            "Run the command:\n\ngrep -i tls-min-version *"
+        );
+        assert_eq!(rule.cci_ids, ["CCI-000068", "CCI-001453"]);
+        assert_eq!(rule.status, "open");
+        assert_eq!(rule.finding_details, "tls-min-version is not set");
+        assert_eq!(rule.severity_override.as_deref(), Some("CAT II"));
+        assert_eq!(
+            rule.severity_justification.as_deref(),
+            Some("Isolated network")
+        );
+
+        // Older checklists omit Rule_Ver and Severity
+        assert_eq!(rules[1].stig_id, "V-242377");
+        assert_eq!(rules[1].severity, "CAT II");
+        assert_eq!(rules[1].status, "not_a_finding");
+    }
+
+    #[test]
+    fn rejects_documents_that_are_not_checklists() {
+        assert!(parse_ckl_reader("<Benchmark/>".as_bytes()).is_err());
+        assert!(parse_ckl_reader("<CHECKLIST><ASSET/></CHECKLIST>".as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,373 @@
+//! STIG Viewer 3 `.cklb` checklists.
+//!
+//! CKLB is JSON: `target_data` describes the asset and `stigs[]` holds each
+//! benchmark with its `rules[]`, every one identified by a uuid.  The structs
+//! below mirror that layout; `parse_cklb_reader` converts it into the same
+//! `Checklist` the CKL parser produces, and `export::cklb` goes the other way.
+//!
+//! STIG Viewer 3 writes `null` for many empty text fields, so those are
+//! `Option`s here.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+
+use super::ckl::{Asset, Checklist, ChecklistStig};
+use super::{
+    clean_description, map_severity, normalize_block, CheckContentRef, Reference, Rule, StigData,
+};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Cklb {
+    pub title: Option<String>,
+    pub id: Option<String>,
+    pub active: bool,
+    pub mode: i64,
+    pub has_path: bool,
+    pub target_data: CklbTarget,
+    pub stigs: Vec<CklbStig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cklb_version: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CklbTarget {
+    pub target_type: Option<String>,
+    pub host_name: Option<String>,
+    pub ip_address: Option<String>,
+    pub mac_address: Option<String>,
+    pub fqdn: Option<String>,
+    pub comments: Option<String>,
+    pub role: Option<String>,
+    pub is_web_database: bool,
+    pub technology_area: Option<String>,
+    pub web_db_site: Option<String>,
+    pub web_db_instance: Option<String>,
+    pub classification: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CklbStig {
+    pub stig_name: Option<String>,
+    pub display_name: Option<String>,
+    pub stig_id: Option<String>,
+    pub release_info: Option<String>,
+    pub version: Option<String>,
+    pub uuid: Option<String>,
+    pub reference_identifier: Option<String>,
+    pub size: usize,
+    pub rules: Vec<CklbRule>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CklbRule {
+    pub uuid: Option<String>,
+    pub stig_uuid: Option<String>,
+    pub target_key: Option<String>,
+    pub stig_ref: Option<String>,
+    pub group_id: Option<String>,
+    /// `SV-242376r960759`; `rule_id_src` carries the `_rule` suffix.
+    pub rule_id: Option<String>,
+    pub rule_id_src: Option<String>,
+    pub weight: Option<String>,
+    pub classification: Option<String>,
+    /// `high`, `medium` or `low`.
+    pub severity: Option<String>,
+    pub rule_version: Option<String>,
+    pub group_title: Option<String>,
+    pub rule_title: Option<String>,
+    pub fix_text: Option<String>,
+    pub false_positives: Option<String>,
+    pub false_negatives: Option<String>,
+    pub discussion: Option<String>,
+    pub check_content: Option<String>,
+    pub documentable: Option<String>,
+    pub mitigations: Option<String>,
+    pub potential_impacts: Option<String>,
+    pub third_party_tools: Option<String>,
+    pub mitigation_control: Option<String>,
+    pub responsibility: Option<String>,
+    pub security_override_guidance: Option<String>,
+    pub ia_controls: Option<String>,
+    pub check_content_ref: Option<CklbCheckContentRef>,
+    pub legacy_ids: Vec<String>,
+    pub ccis: Vec<String>,
+    pub group_tree: Vec<CklbGroup>,
+    /// Same values as `Rule.status`: `not_a_finding`, `open`, …
+    pub status: Option<String>,
+    /// `{"severity": {"severity": "low", "reason": "…"}}` when overridden.
+    pub overrides: serde_json::Value,
+    pub comments: Option<String>,
+    pub finding_details: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CklbCheckContentRef {
+    pub href: Option<String>,
+    pub name: Option<String>,
+}
+
+/// An entry of a rule's `group_tree` — the Group it sits in.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CklbGroup {
+    pub id: Option<String>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+}
+
+/// Parse a `.cklb` document.
+pub fn parse_cklb_reader<R: Read>(json: R) -> Result<Checklist> {
+    let cklb: Cklb = serde_json::from_reader(json).context("Invalid CKLB JSON")?;
+    if cklb.stigs.is_empty() {
+        bail!("CKLB contains no stigs");
+    }
+
+    let target = cklb.target_data;
+    let asset = Asset {
+        role: target.role.unwrap_or_default(),
+        asset_type: target.target_type.unwrap_or_default(),
+        marking: target.classification.unwrap_or_default(),
+        host_name: target.host_name.unwrap_or_default(),
+        host_ip: target.ip_address.unwrap_or_default(),
+        host_mac: target.mac_address.unwrap_or_default(),
+        host_fqdn: target.fqdn.unwrap_or_default(),
+        target_comment: target.comments.unwrap_or_default(),
+        tech_area: target.technology_area.unwrap_or_default(),
+        target_key: cklb
+            .stigs
+            .iter()
+            .find_map(|s| s.reference_identifier.clone())
+            .unwrap_or_default(),
+        web_or_database: target.is_web_database,
+        web_db_site: target.web_db_site.unwrap_or_default(),
+        web_db_instance: target.web_db_instance.unwrap_or_default(),
+    };
+
+    let stigs = cklb.stigs.into_iter().map(cklb_stig_to_checklist).collect();
+    Ok(Checklist {
+        asset,
+        stigs,
+        review: None,
+    })
+}
+
+fn cklb_stig_to_checklist(stig: CklbStig) -> ChecklistStig {
+    let reference_identifier = stig.reference_identifier.clone();
+    ChecklistStig {
+        catalog_id: None,
+        uuid: stig.uuid,
+        classification: None,
+        filename: None,
+        stig: StigData {
+            benchmark_id: stig.stig_id.unwrap_or_default(),
+            title: stig
+                .stig_name
+                .or(stig.display_name)
+                .unwrap_or_else(|| "Imported Checklist".to_string()),
+            version: stig.version.unwrap_or_default(),
+            release_info: stig.release_info.unwrap_or_default(),
+            rules: stig
+                .rules
+                .into_iter()
+                .map(|rule| cklb_rule_to_rule(rule, reference_identifier.as_deref()))
+                .collect(),
+            ..Default::default()
+        },
+    }
+}
+
+fn cklb_rule_to_rule(rule: CklbRule, reference_identifier: Option<&str>) -> Rule {
+    let non_empty = |value: Option<String>| value.filter(|v| !v.is_empty());
+    let group = rule.group_tree.into_iter().next().unwrap_or_default();
+    let group_title = rule.group_title.or(group.title).unwrap_or_default();
+    let severity_override = rule.overrides.get("severity");
+    let override_field = |key: &str| {
+        severity_override
+            .and_then(|o| o.get(key))
+            .and_then(|v| v.as_str())
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+    };
+    let group_id = rule.group_id.or(group.id).unwrap_or_default();
+
+    Rule {
+        id: rule.rule_id_src.or(rule.rule_id).unwrap_or_default(),
+        uuid: rule.uuid,
+        stig_id: non_empty(rule.rule_version).unwrap_or_else(|| group_id.clone()),
+        group_id,
+        srg_id: group_title.starts_with("SRG-").then(|| group_title.clone()),
+        group_title,
+        group_description: group
+            .description
+            .map(|d| clean_description(&d))
+            .filter(|d| !d.is_empty()),
+        title: rule.rule_title.unwrap_or_default(),
+        severity: map_severity(rule.severity.as_deref().unwrap_or("medium")).to_string(),
+        weight: non_empty(rule.weight),
+        description: normalize_block(&rule.discussion.unwrap_or_default()),
+        fix_text: normalize_block(&rule.fix_text.unwrap_or_default()),
+        check_text: normalize_block(&rule.check_content.unwrap_or_default()),
+        check_content_ref: rule.check_content_ref.map(|r| CheckContentRef {
+            href: r.href.unwrap_or_default(),
+            name: r.name.unwrap_or_default(),
+        }),
+        false_positives: non_empty(rule.false_positives),
+        false_negatives: non_empty(rule.false_negatives),
+        documentable: non_empty(rule.documentable),
+        mitigations: non_empty(rule.mitigations),
+        severity_override_guidance: non_empty(rule.security_override_guidance),
+        potential_impacts: non_empty(rule.potential_impacts),
+        third_party_tools: non_empty(rule.third_party_tools),
+        mitigation_control: non_empty(rule.mitigation_control),
+        responsibility: non_empty(rule.responsibility),
+        ia_controls: non_empty(rule.ia_controls),
+        cci_ids: rule.ccis,
+        legacy_ids: rule.legacy_ids,
+        references: non_empty(rule.target_key)
+            .or(reference_identifier.map(str::to_string))
+            .map(|identifier| Reference {
+                publisher: "DISA".to_string(),
+                ref_type: "DPMS Target".to_string(),
+                identifier,
+                ..Default::default()
+            })
+            .into_iter()
+            .collect(),
+        status: match rule.status.as_deref() {
+            Some(status @ ("not_a_finding" | "open" | "not_applicable")) => status.to_string(),
+            _ => "not_reviewed".to_string(),
+        },
+        finding_details: rule.finding_details.unwrap_or_default(),
+        comments: rule.comments.unwrap_or_default(),
+        severity_override: override_field("severity").map(|s| map_severity(&s).to_string()),
+        severity_justification: override_field("reason"),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CKLB: &str = r#"{
+      "title": "k8s-cp01",
+      "id": "8b5c9d8e-0000-4000-8000-000000000000",
+      "active": false,
+      "mode": 2,
+      "has_path": true,
+      "target_data": {
+        "target_type": "Computing",
+        "host_name": "k8s-cp01",
+        "ip_address": "10.0.0.5",
+        "mac_address": null,
+        "fqdn": null,
+        "comments": null,
+        "role": "Member Server",
+        "is_web_database": false,
+        "technology_area": null,
+        "web_db_site": null,
+        "web_db_instance": null,
+        "classification": "CUI"
+      },
+      "stigs": [{
+        "stig_name": "Kubernetes Security Technical Implementation Guide",
+        "display_name": "Kubernetes",
+        "stig_id": "Kubernetes_STIG",
+        "release_info": "Release: 5 Benchmark Date: 05 Jan 2026",
+        "version": "2",
+        "uuid": "3b1a2c4d-0000-4000-8000-000000000001",
+        "reference_identifier": "5289",
+        "size": 2,
+        "rules": [{
+          "uuid": "0c8f0000-0000-4000-8000-000000000002",
+          "group_id": "V-242376",
+          "rule_id": "SV-242376r960759",
+          "rule_id_src": "SV-242376r960759_rule",
+          "severity": "high",
+          "rule_version": "CNTR-K8-000150",
+          "group_title": "SRG-APP-000014-CTR-000035",
+          "rule_title": "Use TLS 1.2.",
+          "check_content": "Run the command:\r\n\r\ngrep -i tls *",
+          "mitigations": "",
+          "ccis": ["CCI-000068"],
+          "status": "open",
+          "overrides": {"severity": {"severity": "low", "reason": "Isolated network"}},
+          "comments": null,
+          "finding_details": "not set"
+        }, {
+          "rule_id": "SV-242377r960759",
+          "rule_version": "",
+          "group_tree": [{"id": "V-242377", "title": "SRG-APP-000014-CTR-000040"}],
+          "status": "Open",
+          "overrides": {}
+        }]
+      }]
+    }"#;
+
+    #[test]
+    fn parses_target_data_into_asset() {
+        let checklist = parse_cklb_reader(CKLB.as_bytes()).unwrap();
+        assert_eq!(checklist.asset.host_name, "k8s-cp01");
+        assert_eq!(checklist.asset.host_ip, "10.0.0.5");
+        assert_eq!(checklist.asset.host_mac, "");
+        assert_eq!(checklist.asset.marking, "CUI");
+        assert_eq!(checklist.asset.target_key, "5289");
+
+        let stig = &checklist.stigs[0];
+        assert_eq!(
+            stig.uuid.as_deref(),
+            Some("3b1a2c4d-0000-4000-8000-000000000001")
+        );
+        assert_eq!(stig.stig.benchmark_id, "Kubernetes_STIG");
+        assert_eq!(
+            stig.stig.title,
+            "Kubernetes Security Technical Implementation Guide"
+        );
+    }
+
+    #[test]
+    fn parses_rules_with_review_and_overrides() {
+        let checklist = parse_cklb_reader(CKLB.as_bytes()).unwrap();
+        let rule = &checklist.stigs[0].stig.rules[0];
+        assert_eq!(rule.id, "SV-242376r960759_rule");
+        assert_eq!(rule.stig_id, "CNTR-K8-000150");
+        assert_eq!(rule.srg_id.as_deref(), Some("SRG-APP-000014-CTR-000035"));
+        assert_eq!(rule.severity, "CAT I");
+        assert_eq!(rule.check_text, "Run the command:\n\ngrep -i tls *");
+        assert_eq!(rule.mitigations, None);
+        assert_eq!(rule.status, "open");
+        assert_eq!(rule.comments, "");
+        assert_eq!(rule.severity_override.as_deref(), Some("CAT III"));
+        assert_eq!(
+            rule.severity_justification.as_deref(),
+            Some("Isolated network")
+        );
+        assert_eq!(rule.references[0].identifier, "5289");
+    }
+
+    #[test]
+    fn falls_back_to_group_tree_and_unreviewed() {
+        let checklist = parse_cklb_reader(CKLB.as_bytes()).unwrap();
+        let rule = &checklist.stigs[0].stig.rules[1];
+        assert_eq!(rule.id, "SV-242377r960759");
+        assert_eq!(rule.group_id, "V-242377");
+        assert_eq!(rule.stig_id, "V-242377");
+        assert_eq!(rule.group_title, "SRG-APP-000014-CTR-000040");
+        assert_eq!(rule.severity, "CAT II");
+        // Only the lower-case CKLB statuses are recognised
+        assert_eq!(rule.status, "not_reviewed");
+        assert_eq!(rule.severity_override, None);
+    }
+
+    #[test]
+    fn rejects_cklb_without_stigs() {
+        assert!(parse_cklb_reader(r#"{"title": "empty", "stigs": []}"#.as_bytes()).is_err());
+        assert!(parse_cklb_reader("<CHECKLIST/>".as_bytes()).is_err());
+    }
+}
@@ -0,0 +1,236 @@
+//! Decoding XML documents that are not UTF-8.
+//!
+//! quick-xml only reads UTF-8, but some older DISA and vendor benchmarks are
+//! UTF-16 with a byte order mark or declare `encoding="windows-1252"`.  The
+//! encoding is detected as in appendix F of the XML specification — a BOM,
+//! then the byte pattern of `<?xml`, then the declaration — and anything
+//! other than UTF-8 is transcoded while it is read.
+
+use anyhow::{bail, Result};
+use encoding_rs::{CoderResult, Decoder, Encoding, UTF_16BE, UTF_16LE, UTF_8};
+use std::io::{self, BufRead, BufReader, Cursor, Read};
+
+/// Bytes read ahead to find the XML declaration.
+const SNIFF_BYTES: usize = 1024;
+
+/// Wrap `xml` so that it yields UTF-8, whatever its BOM or declared encoding.
+///
+/// Fails only for an encoding label encoding_rs does not know.
+pub fn utf8_xml<'a, R: BufRead + 'a>(mut xml: R) -> Result<Box<dyn BufRead + 'a>> {
+    let mut head = Vec::with_capacity(SNIFF_BYTES);
+    (&mut xml).take(SNIFF_BYTES as u64).read_to_end(&mut head)?;
+
+    let (encoding, bom_len) = detect_encoding(&head)?;
+    let mut head = Cursor::new(head);
+    head.set_position(bom_len as u64);
+    let rest = head.chain(xml);
+
+    if encoding == UTF_8 {
+        return Ok(Box::new(rest));
+    }
+    Ok(Box::new(BufReader::new(Transcoder {
+        inner: rest,
+        decoder: encoding.new_decoder_without_bom_handling(),
+        out: Vec::new(),
+        out_pos: 0,
+        done: false,
+    })))
+}
+
+/// Encoding of a document from its first bytes, and the length of its BOM.
+fn detect_encoding(head: &[u8]) -> Result<(&'static Encoding, usize)> {
+    if let Some(found) = Encoding::for_bom(head) {
+        return Ok(found);
+    }
+    // `<?` without a BOM
+    match head {
+        [0x3C, 0x00, 0x3F, 0x00, ..] => return Ok((UTF_16LE, 0)),
+        [0x00, 0x3C, 0x00, 0x3F, ..] => return Ok((UTF_16BE, 0)),
+        _ => {}
+    }
+
+    let Some(label) = declared_encoding(head) else {
+        return Ok((UTF_8, 0));
+    };
+    match Encoding::for_label(label.as_bytes()) {
+        // The declaration was readable as ASCII, so it cannot really be UTF-16
+        Some(encoding) if encoding == UTF_16LE || encoding == UTF_16BE => Ok((UTF_8, 0)),
+        Some(encoding) => Ok((encoding, 0)),
+        None => bail!("Unsupported XML encoding '{label}'"),
+    }
+}
+
+/// `encoding` pseudo-attribute of the `<?xml … ?>` declaration, if any.
+fn declared_encoding(head: &[u8]) -> Option<String> {
+    let decl = head.strip_prefix(b"<?xml")?;
+    let decl = &decl[..decl.windows(2).position(|w| w == b"?>")?];
+    let decl = String::from_utf8_lossy(decl);
+
+    let rest = decl.split_once("encoding")?.1.trim_start();
+    let rest = rest.strip_prefix('=')?.trim_start();
+    let quote = rest.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let value = &rest[1..];
+    Some(value[..value.find(quote)?].trim().to_string())
+}
+
+/// Streams UTF-8 out of a reader in another encoding.  Malformed input is
+/// replaced with U+FFFD rather than failing the document.
+struct Transcoder<R> {
+    inner: R,
+    decoder: Decoder,
+    out: Vec<u8>,
+    out_pos: usize,
+    done: bool,
+}
+
+impl<R: BufRead> Read for Transcoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.out_pos == self.out.len() && !self.done {
+            let src = self.inner.fill_buf()?;
+            let last = src.is_empty();
+            let mut out = vec![
+                0u8;
+                self.decoder
+                    .max_utf8_buffer_length(src.len())
+                    .unwrap_or(0)
+                    .max(16)
+            ];
+            let (result, read, written, _) = self.decoder.decode_to_utf8(src, &mut out, last);
+            self.inner.consume(read);
+            out.truncate(written);
+            self.out = out;
+            self.out_pos = 0;
+            self.done = last && result == CoderResult::InputEmpty;
+        }
+
+        let n = buf.len().min(self.out.len() - self.out_pos);
+        buf[..n].copy_from_slice(&self.out[self.out_pos..self.out_pos + n]);
+        self.out_pos += n;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Non-ASCII text, including a character outside the BMP.
+    const TEXT: &str = "Über “quoted” café – ✓ 𝄞";
+
+    fn document(encoding: &str) -> String {
+        format!("<?xml version=\"1.0\" encoding=\"{encoding}\"?>\n<title>{TEXT}</title>")
+    }
+
+    fn utf16(text: &str, big_endian: bool, bom: bool) -> Vec<u8> {
+        let units =
+            (bom.then_some('\u{FEFF}').into_iter().map(|c| c as u16)).chain(text.encode_utf16());
+        units
+            .flat_map(|u| {
+                if big_endian {
+                    u.to_be_bytes()
+                } else {
+                    u.to_le_bytes()
+                }
+            })
+            .collect()
+    }
+
+    fn decode<R: BufRead>(xml: R) -> String {
+        let mut out = String::new();
+        utf8_xml(xml).unwrap().read_to_string(&mut out).unwrap();
+        out
+    }
+
+    /// Hands out one byte per read, so every multi-byte sequence is split.
+    struct OneByte<'a>(&'a [u8]);
+
+    impl Read for OneByte<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = self.fill_buf()?.len().min(buf.len());
+            buf[..n].copy_from_slice(&self.0[..n]);
+            self.consume(n);
+            Ok(n)
+        }
+    }
+
+    impl BufRead for OneByte<'_> {
+        fn fill_buf(&mut self) -> io::Result<&[u8]> {
+            Ok(&self.0[..self.0.len().min(1)])
+        }
+
+        fn consume(&mut self, amt: usize) {
+            self.0 = &self.0[amt..];
+        }
+    }
+
+    #[test]
+    fn utf8_passes_through() {
+        let xml = document("UTF-8");
+        assert_eq!(decode(xml.as_bytes()), xml);
+        let with_bom = format!("\u{FEFF}{xml}");
+        assert_eq!(decode(with_bom.as_bytes()), xml);
+    }
+
+    #[test]
+    fn utf16_with_and_without_bom() {
+        let xml = document("UTF-16");
+        for big_endian in [false, true] {
+            for bom in [false, true] {
+                let bytes = utf16(&xml, big_endian, bom);
+                assert_eq!(decode(&bytes[..]), xml, "big_endian={big_endian} bom={bom}");
+            }
+        }
+    }
+
+    #[test]
+    fn windows_1252_declaration() {
+        let xml =
+            "<?xml version=\"1.0\" encoding=\"windows-1252\"?>\n<t>Über “quoted” café – €5</t>";
+        let (bytes, _, lossy) = encoding_rs::WINDOWS_1252.encode(xml);
+        assert!(!lossy);
+        // Quotes, dash and euro are in 0x80–0x9F, where 1252 differs from Latin-1
+        assert!([0x93, 0x94, 0x96, 0x80].iter().all(|b| bytes.contains(b)));
+        assert_eq!(decode(&bytes[..]), xml);
+    }
+
+    #[test]
+    fn input_split_across_reads() {
+        // Longer than the sniffed head, so the rest comes from the reader
+        let body = TEXT.repeat(200);
+        let xml = format!("<?xml version=\"1.0\" encoding=\"UTF-16\"?><title>{body}</title>");
+        for big_endian in [false, true] {
+            let bytes = utf16(&xml, big_endian, true);
+            assert!(bytes.len() > 2 * SNIFF_BYTES);
+            assert_eq!(decode(OneByte(&bytes)), xml, "big_endian={big_endian}");
+        }
+
+        let body = "“é”".repeat(600);
+        let xml = format!("<?xml version=\"1.0\" encoding=\"windows-1252\"?><t>{body}</t>");
+        let (bytes, _, _) = encoding_rs::WINDOWS_1252.encode(&xml);
+        assert_eq!(decode(OneByte(&bytes)), xml);
+    }
+
+    #[test]
+    fn malformed_input_is_replaced() {
+        // An unpaired high surrogate
+        let mut bytes = utf16("<?xml version=\"1.0\"?><t>", false, true);
+        bytes.extend_from_slice(&0xD834u16.to_le_bytes());
+        bytes.extend_from_slice(&utf16("</t>", false, false));
+        assert_eq!(decode(&bytes[..]), "<?xml version=\"1.0\"?><t>\u{FFFD}</t>");
+    }
+
+    #[test]
+    fn detects_encoding_from_declaration() {
+        assert_eq!(detect_encoding(b"<root/>").unwrap(), (UTF_8, 0));
+        assert_eq!(
+            detect_encoding(b"<?xml version='1.0' encoding='ISO-8859-1'?>")
+                .unwrap()
+                .0,
+            encoding_rs::WINDOWS_1252
+        );
+        // Readable as ASCII, so the UTF-16 declaration is wrong
+        let declared = detect_encoding(b"<?xml version=\"1.0\" encoding=\"UTF-16\"?>").unwrap();
+        assert_eq!(declared.0, UTF_8);
+        assert!(detect_encoding(b"<?xml version=\"1.0\" encoding=\"x-klingon\"?>").is_err());
+    }
+}
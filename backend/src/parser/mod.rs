@@ -1,10 +1,36 @@
 use anyhow::{bail, Result};
+use chrono::NaiveDate;
 use quick_xml::events::Event;
 use quick_xml::Reader;
 use serde::{Deserialize, Serialize};
+use std::io::{BufRead, Read, Seek};
+
+pub mod archive;
+pub mod ckl;
+pub mod cklb;
+pub mod encoding;
+pub mod results;
+pub mod warnings;
+
+use archive::{walk_archive, EntryKind, Walked, ZipLimits};
+use warnings::{Diagnostics, ParseWarning};
+
+/// Bumped whenever parsing changes what is written to a STIG's JSON, so that
+/// files written by an older release can be found and re-imported.
+///
+/// 1. `stigId` is the Rule's `<version>` rather than the Group's V-number.
+/// 2. Rule titles are no longer the `dc:title` of the rule's `<reference>`
+///    ("DPMS Target …"), which is kept in `references` instead.  Rules also
+///    gain `legacyIds` and other `idents` filed by system, the VulnDiscussion
+///    sub-fields, the check system and content ref, fix id and weight, and
+///    their Group's title, SRG id and description; check and fix text keep
+///    their line breaks.  Benchmarks gain their `profiles` and metadata:
+///    status and date, notices, front and rear matter, references, platforms
+///    and plain texts.  SCAP data-stream benchmarks are parsed too.
+pub const PARSER_VERSION: u32 = 2;
 
 /// Maps XCCDF severity strings to the CAT labels the frontend uses.
-fn map_severity(s: &str) -> &'static str {
+pub(crate) fn map_severity(s: &str) -> &'static str {
     match s.to_lowercase().as_str() {
         "high" => "CAT I",
         "low" => "CAT III",
@@ -12,49 +38,260 @@ fn map_severity(s: &str) -> &'static str {
     }
 }
 
+/// Maps a CAT label back to the XCCDF severity written to CKL/CKLB/XCCDF.
+pub(crate) fn xccdf_severity(cat: &str) -> &'static str {
+    match cat {
+        "CAT I" => "high",
+        "CAT III" => "low",
+        _ => "medium",
+    }
+}
+
 /// A single STIG rule — matches the shape produced by the frontend's parseXCCDF.js.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Rule {
     pub id: String,
+    /// Rule uuid from a STIG Viewer 3 checklist, kept for re-export.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<String>,
+    /// DISA STIG ID from the Rule's `<version>`, e.g. `WN11-00-000010`.
     pub stig_id: String,
+    /// Vulnerability ID from the enclosing Group, e.g. `V-253254`.
     pub group_id: String,
+    /// The enclosing Group's `<title>` as published.
+    #[serde(default)]
+    pub group_title: String,
+    /// SRG requirement this rule implements — the Group title when it is an `SRG-` id.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub srg_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group_description: Option<String>,
     pub title: String,
     pub severity: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight: Option<String>,
     pub description: String,
     pub fix_text: String,
+    /// `fixref` of the `<fixtext>`, pointing at the `<fix id>` below.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fix_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fix_id: Option<String>,
     pub check_text: String,
+    /// `<check system>` — a `C-…_chk` id for manual checks, an OVAL/OCIL URI for SCAP.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub check_system: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub check_content_ref: Option<CheckContentRef>,
+    // VulnDiscussion siblings embedded in the rule description; omitted when empty.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub false_positives: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub false_negatives: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub documentable: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mitigations: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity_override_guidance: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub potential_impacts: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub third_party_tools: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mitigation_control: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub responsibility: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ia_controls: Option<String>,
+    /// Identifiers from `http://cyber.mil/cci` idents only.
     pub cci_ids: Vec<String>,
+    /// Pre-SV vulnerability IDs (`V-63319`, `SV-77809`) from legacy idents.
+    #[serde(default)]
+    pub legacy_ids: Vec<String>,
+    /// Any other `<ident>` whose system is neither CCI nor legacy.
+    #[serde(default)]
+    pub idents: Vec<Ident>,
+    #[serde(default)]
+    pub references: Vec<Reference>,
     pub status: String,
     pub finding_details: String,
     pub comments: String,
+    /// Reviewer's severity override (`CAT I`–`CAT III`) and its justification.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity_override: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity_justification: Option<String>,
+}
+
+/// An `<ident>` value together with the URI of the system that issued it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Ident {
+    pub system: String,
+    pub value: String,
+}
+
+/// `<check-content-ref>` — where the check content lives (`name="M"` means manual).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckContentRef {
+    pub href: String,
+    pub name: String,
+}
+
+/// A Dublin Core `<reference>` attached to a rule (typically the DPMS target).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Reference {
+    pub publisher: String,
+    #[serde(rename = "type")]
+    pub ref_type: String,
+    pub subject: String,
+    pub identifier: String,
+}
+
+/// A Benchmark-level `<reference>`, e.g. `href="https://cyber.mil"` with its
+/// `dc:publisher` and `dc:source`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmarkReference {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub href: Option<String>,
+    pub publisher: String,
+    pub source: String,
+}
+
+/// A `<notice>` (DISA ships an empty `terms-of-use` notice) or a
+/// `<plain-text>` block such as `release-info` or `generator`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextBlock {
+    pub id: String,
+    pub text: String,
+}
+
+/// A benchmark `<Profile>` (e.g. `MAC-1_Classified`) and the rules it selects.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Profile {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    /// `idref`s of every `<select selected="true">` — Group or Rule ids.
+    pub selected: Vec<String>,
+}
+
+impl Profile {
+    /// Whether this profile selects the given rule, by its Group or Rule id.
+    pub fn selects(&self, rule: &Rule) -> bool {
+        self.selected
+            .iter()
+            .any(|idref| *idref == rule.group_id || *idref == rule.id)
+    }
 }
 
 /// The top-level STIG object returned by /api/stigs/:id.
 /// Shape must match the frontend's internal STIG model exactly.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct StigData {
+    /// Benchmark `id`, e.g. `Kubernetes_STIG` (XCCDF 1.2 prefix removed).
+    #[serde(default)]
+    pub benchmark_id: String,
     pub title: String,
     pub description: String,
     pub version: String,
+    /// Text of the `release-info` plain-text, e.g.
+    /// `Release: 5 Benchmark Date: 24 Jul 2024`.
     pub release_info: String,
+    /// Latest `<status>`, e.g. `accepted`, and its `date` attribute.
+    #[serde(default)]
+    pub status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status_date: Option<String>,
+    #[serde(default)]
+    pub notices: Vec<TextBlock>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub front_matter: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rear_matter: Option<String>,
+    #[serde(default)]
+    pub references: Vec<BenchmarkReference>,
+    /// CPE names from `<platform idref>`, e.g. `cpe:/o:microsoft:windows_11`.
+    #[serde(default)]
+    pub platforms: Vec<String>,
+    /// Every `<plain-text>` with its `id`, including `release-info`.
+    #[serde(default)]
+    pub plain_texts: Vec<TextBlock>,
+    #[serde(default)]
+    pub profiles: Vec<Profile>,
     pub rules: Vec<Rule>,
+    /// Content problems found while parsing; kept in the catalog rather than
+    /// the STIG's JSON file.
+    #[serde(skip)]
+    pub warnings: Vec<ParseWarning>,
+    /// [`PARSER_VERSION`] of the parser that produced this; absent in JSON
+    /// written before versions were recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parser_version: Option<u32>,
+}
+
+impl StigData {
+    /// Whether this was parsed by an older release and should be re-imported.
+    pub fn is_stale(&self) -> bool {
+        self.parser_version.unwrap_or(0) < PARSER_VERSION
+    }
+
+    /// Distinct SRG ids implemented by the rules, sorted.
+    pub fn srg_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self.rules.iter().filter_map(|r| r.srg_id.clone()).collect();
+        ids.sort();
+        ids.dedup();
+        ids
+    }
 }
 
 // ── Helpers ──────────────────────────────────────────────────────────────────
 
-/// Strip known XCCDF XML tags from description text (mirrors cleanDescription in parseXCCDF.js).
-fn clean_description(raw: &str) -> String {
-    // Tags to strip completely (with their contents)
-    const STRIP_TAGS: &[&str] = &[
-        "FalsePositives", "FalseNegatives", "Documentable", "Mitigations",
-        "SeverityOverrideGuidance", "PotentialImpacts", "ThirdPartyTools",
-        "MitigationControl", "Responsibility", "IAControls",
-    ];
+/// Append a line of text to a block that may span several text nodes.
+fn push_line(block: &mut String, text: &str) {
+    if !block.is_empty() {
+        block.push('\n');
+    }
+    block.push_str(text);
+}
+
+/// File an `<ident>` under the right list based on its `system` URI.
+///
+/// DISA has published under both `cyber.mil` and the older `iase.disa.mil`
+/// namespaces; idents without a system fall back to the value's prefix.
+fn push_ident(rule: &mut Rule, system: &str, value: String) {
+    let kind = system.trim_end_matches('/').rsplit('/').next().unwrap_or("");
+    match kind {
+        "cci" => rule.cci_ids.push(value),
+        "legacy" => rule.legacy_ids.push(value),
+        "" if value.starts_with("CCI-") => rule.cci_ids.push(value),
+        _ => rule.idents.push(Ident {
+            system: system.to_string(),
+            value,
+        }),
+    }
+}
+
+/// Pseudo-elements DISA embeds next to `<VulnDiscussion>` in a rule description.
+const DISCUSSION_TAGS: &[&str] = &[
+    "FalsePositives", "FalseNegatives", "Documentable", "Mitigations",
+    "SeverityOverrideGuidance", "PotentialImpacts", "ThirdPartyTools",
+    "MitigationControl", "Responsibility", "IAControls",
+];
 
+/// Strip known XCCDF XML tags from description text (mirrors cleanDescription in parseXCCDF.js).
+pub(crate) fn clean_description(raw: &str) -> String {
+    // Discussion sub-fields are stripped completely (with their contents)
     let mut s = raw.to_string();
-    for tag in STRIP_TAGS {
+    for tag in DISCUSSION_TAGS {
         let open = format!("<{tag}>");
         let close = format!("</{tag}>");
         while let (Some(start), Some(end)) = (s.find(&open), s.find(&close)) {
@@ -86,12 +323,128 @@ fn clean_description(raw: &str) -> String {
         }
     }
 
-    // Collapse whitespace
-    result.split_whitespace().collect::<Vec<_>>().join(" ")
+    normalize_block(&result)
+}
+
+/// Tidy a multi-line text block without losing its line structure.
+///
+/// Trailing spaces are dropped, the indentation shared by the lines after the
+/// first is removed (the first line starts right after the opening tag), runs
+/// of blank lines collapse to one, and leading/trailing blank lines go.
+pub(crate) fn normalize_block(raw: &str) -> String {
+    let raw = raw.replace("\r\n", "\n").replace('\r', "\n");
+    let lines: Vec<&str> = raw.lines().map(str::trim_end).collect();
+    let indent = lines
+        .iter()
+        .skip(1)
+        .filter(|line| !line.is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+
+    let mut out = String::with_capacity(raw.len());
+    let mut pending_blank = false;
+    for (i, line) in lines.iter().enumerate() {
+        let line = match i {
+            0 => line.trim_start(),
+            _ => line.get(indent..).unwrap_or_else(|| line.trim_start()),
+        };
+        if line.is_empty() {
+            pending_blank = !out.is_empty();
+            continue;
+        }
+        if !out.is_empty() {
+            out.push_str(if pending_blank { "\n\n" } else { "\n" });
+        }
+        out.push_str(line);
+        pending_blank = false;
+    }
+    out
+}
+
+/// Copy each non-empty discussion sub-field of a raw rule description onto the rule.
+///
+/// Tags may repeat (several `<Responsibility>` entries are common); their values
+/// are joined one per line.
+fn apply_discussion_fields(rule: &mut Rule, raw: &str) {
+    for tag in DISCUSSION_TAGS {
+        let close = format!("</{tag}>");
+        let mut values = Vec::new();
+        let mut rest = raw;
+        while let Some(value) = child_text(rest.as_bytes(), tag) {
+            if !value.is_empty() {
+                values.push(normalize_block(&value));
+            }
+            match rest.find(&close) {
+                Some(end) => rest = &rest[end + close.len()..],
+                None => break,
+            }
+        }
+        if values.is_empty() {
+            continue;
+        }
+        let value = values.join("\n");
+        let slot = match *tag {
+            "FalsePositives" => &mut rule.false_positives,
+            "FalseNegatives" => &mut rule.false_negatives,
+            "Documentable" => &mut rule.documentable,
+            "Mitigations" => &mut rule.mitigations,
+            "SeverityOverrideGuidance" => &mut rule.severity_override_guidance,
+            "PotentialImpacts" => &mut rule.potential_impacts,
+            "ThirdPartyTools" => &mut rule.third_party_tools,
+            "MitigationControl" => &mut rule.mitigation_control,
+            "Responsibility" => &mut rule.responsibility,
+            "IAControls" => &mut rule.ia_controls,
+            _ => continue,
+        };
+        *slot = Some(value);
+    }
+}
+
+/// Record the attributes of check/fix elements inside a Rule.
+///
+/// Called for both start and self-closing tags, since `<fix>` and
+/// `<check-content-ref>` are usually written as the latter.
+fn apply_rule_attrs(rule: &mut Rule, local: &str, tag_bytes: &[u8]) {
+    match local {
+        "check" => rule.check_system = attr_value(tag_bytes, "system"),
+        "check-content-ref" => {
+            rule.check_content_ref = Some(CheckContentRef {
+                href: attr_value(tag_bytes, "href").unwrap_or_default(),
+                name: attr_value(tag_bytes, "name").unwrap_or_default(),
+            })
+        }
+        "fixtext" | "fix-text" => rule.fix_ref = attr_value(tag_bytes, "fixref"),
+        "fix" => rule.fix_id = attr_value(tag_bytes, "id"),
+        _ => {}
+    }
+}
+
+/// Return the `idref` of a `<select>` element if it marks the item as selected.
+fn selected_idref(tag_bytes: &[u8]) -> Option<String> {
+    match attr_value(tag_bytes, "selected").as_deref() {
+        Some("true") | Some("1") => attr_value(tag_bytes, "idref").map(short_id),
+        _ => None,
+    }
+}
+
+/// Strip the XCCDF 1.2 id prefix used by SCAP benchmarks, so that
+/// `xccdf_mil.disa.stig_rule_SV-253254r991589_rule` and the manual
+/// benchmark's `SV-253254r991589_rule` refer to the same rule.
+pub(crate) fn short_id(id: String) -> String {
+    if !id.starts_with("xccdf_") {
+        return id;
+    }
+    for kind in ["_benchmark_", "_profile_", "_group_", "_rule_", "_value_"] {
+        if let Some(pos) = id.find(kind) {
+            return id[pos + kind.len()..].to_string();
+        }
+    }
+    id
 }
 
 /// Extract text content of the first child element with the given local name.
-fn child_text<'a>(parent_bytes: &'a [u8], tag: &str) -> Option<String> {
+fn child_text(parent_bytes: &[u8], tag: &str) -> Option<String> {
     // We use a simple substring search since quick-xml events are finer-grained;
     // this helper is used on already-extracted text buffers.
     let open = format!("<{tag}");
@@ -109,7 +462,7 @@ fn child_text<'a>(parent_bytes: &'a [u8], tag: &str) -> Option<String> {
 }
 
 /// Read an XML attribute value from a start element's raw bytes.
-fn attr_value(tag_bytes: &[u8], attr_name: &str) -> Option<String> {
+pub(crate) fn attr_value(tag_bytes: &[u8], attr_name: &str) -> Option<String> {
     let needle = format!("{attr_name}=\"");
     let start = tag_bytes
         .windows(needle.len())
@@ -120,113 +473,359 @@ fn attr_value(tag_bytes: &[u8], attr_name: &str) -> Option<String> {
     Some(String::from_utf8_lossy(&rest[..end]).to_string())
 }
 
+// ── Release info ─────────────────────────────────────────────────────────────
+
+/// Release number from `Release: 5 Benchmark Date: 05 Jan 2026`.
+pub fn release_number(release_info: &str) -> Option<i32> {
+    let rest = release_info.split("Release:").nth(1)?.trim_start();
+    rest.split(|c: char| !c.is_ascii_digit()).next()?.parse().ok()
+}
+
+/// Benchmark date from `Release: 5 Benchmark Date: 05 Jan 2026`.
+pub fn benchmark_date(release_info: &str) -> Option<NaiveDate> {
+    let rest = release_info.split("Benchmark Date:").nth(1)?;
+    let [day, month, year] = rest.split_whitespace().take(3).collect::<Vec<_>>()[..] else {
+        return None;
+    };
+    // Tolerate spelled-out months ("July", "Sept")
+    let month = month.get(..3)?;
+    NaiveDate::parse_from_str(&format!("{day} {month} {year}"), "%d %b %Y").ok()
+}
+
+/// DISA's `V2R5` label from the benchmark version and release number.
+pub fn release_label(version: &str, release: Option<i32>) -> Option<String> {
+    let version: u32 = version.trim().parse().ok()?;
+    Some(format!("V{version}R{}", release?))
+}
+
 // ── Main parser ───────────────────────────────────────────────────────────────
 
-/// Parse an XCCDF XML string into a `StigData` value.
-pub fn parse_xccdf(xml: &str) -> Result<StigData> {
-    let mut reader = Reader::from_str(xml);
+/// Parse XCCDF XML from a buffered reader into a `StigData` value.
+///
+/// The document is streamed event by event, so a benchmark can be parsed
+/// straight out of a ZIP entry without first reading it into a `String`.
+/// UTF-16 and legacy encodings are transcoded first (see [`encoding`]).
+///
+/// Content problems that do not stop the import are listed in
+/// `StigData::warnings` (see [`warnings`]).
+///
+/// Accepts either a bare `<Benchmark>` document or a SCAP 1.2/1.3
+/// `ds:data-stream-collection`, in which case the XCCDF Benchmark is read
+/// from its `<ds:component>` and the OVAL/CPE components are ignored.
+pub fn parse_xccdf_reader<R: BufRead>(xml: R) -> Result<StigData> {
+    let mut reader = Reader::from_reader(encoding::utf8_xml(xml)?);
     reader.config_mut().trim_text(true);
 
+    let mut benchmark_id = String::new();
+    let mut seen_benchmark = false;
     let mut title = String::new();
     let mut description = String::new();
     let mut version = String::new();
     let mut release_info = String::new();
+    let mut status = String::new();
+    let mut status_date: Option<String> = None;
+    let mut notices: Vec<TextBlock> = Vec::new();
+    let mut front_matter = String::new();
+    let mut rear_matter = String::new();
+    let mut references: Vec<BenchmarkReference> = Vec::new();
+    let mut platforms: Vec<String> = Vec::new();
+    let mut plain_texts: Vec<TextBlock> = Vec::new();
+    let mut profiles: Vec<Profile> = Vec::new();
     let mut rules: Vec<Rule> = Vec::new();
+    let mut diagnostics = Diagnostics::default();
 
     // State machine
     let mut in_benchmark = false;
     let mut in_group = false;
     let mut in_rule = false;
     let mut current_group_id = String::new();
+    let mut current_group_title = String::new();
+    let mut current_group_description = String::new();
+    let mut current_profile: Option<Profile> = None;
     let mut current_rule: Option<Rule> = None;
-    let mut current_tag = String::new();
-    let mut depth: usize = 0;
+    let mut current_reference: Option<Reference> = None;
+    let mut current_ident_system = String::new();
+    // Local names of the currently open elements, outermost first
+    let mut path: Vec<String> = Vec::new();
     let mut rule_depth: usize = 0;
+    let mut benchmark_depth: usize = 0;
 
     let mut buf = Vec::new();
 
     loop {
         match reader.read_event_into(&mut buf) {
             Ok(Event::Start(ref e)) => {
-                depth += 1;
                 let local = String::from_utf8_lossy(e.local_name().as_ref()).to_string();
+                path.push(local);
+                let depth = path.len();
 
-                match local.as_str() {
-                    "Benchmark" => {
+                match path[depth - 1].as_str() {
+                    "Benchmark" if !seen_benchmark => {
                         in_benchmark = true;
+                        seen_benchmark = true;
+                        benchmark_depth = depth;
+                        benchmark_id =
+                            short_id(attr_value(e.as_ref(), "id").unwrap_or_default());
+                    }
+                    // Benchmark-level metadata; text is filled in below
+                    "status" if in_benchmark && depth == benchmark_depth + 1 => {
+                        status_date = attr_value(e.as_ref(), "date");
+                    }
+                    "notice" if in_benchmark && depth == benchmark_depth + 1 => {
+                        notices.push(TextBlock {
+                            id: attr_value(e.as_ref(), "id").unwrap_or_default(),
+                            text: String::new(),
+                        });
+                    }
+                    "plain-text" if in_benchmark && depth == benchmark_depth + 1 => {
+                        plain_texts.push(TextBlock {
+                            id: attr_value(e.as_ref(), "id").unwrap_or_default(),
+                            text: String::new(),
+                        });
+                    }
+                    "reference" if in_benchmark && depth == benchmark_depth + 1 => {
+                        references.push(BenchmarkReference {
+                            href: attr_value(e.as_ref(), "href"),
+                            ..Default::default()
+                        });
+                    }
+                    "Profile" if in_benchmark => {
+                        current_profile = Some(Profile {
+                            id: short_id(attr_value(e.as_ref(), "id").unwrap_or_default()),
+                            title: String::new(),
+                            description: String::new(),
+                            selected: Vec::new(),
+                        });
+                    }
+                    "select" => {
+                        if let (Some(profile), Some(idref)) =
+                            (current_profile.as_mut(), selected_idref(e.as_ref()))
+                        {
+                            profile.selected.push(idref);
+                        }
                     }
                     "Group" if in_benchmark => {
                         in_group = true;
                         current_group_id =
-                            attr_value(e.as_ref(), "id").unwrap_or_default();
+                            short_id(attr_value(e.as_ref(), "id").unwrap_or_default());
+                        diagnostics.group(&current_group_id);
+                        current_group_title.clear();
+                        current_group_description.clear();
                     }
                     "Rule" if in_group => {
                         in_rule = true;
                         rule_depth = depth;
-                        let rule_id = attr_value(e.as_ref(), "id").unwrap_or_default();
+                        let rule_id =
+                            short_id(attr_value(e.as_ref(), "id").unwrap_or_default());
                         let severity_raw =
                             attr_value(e.as_ref(), "severity").unwrap_or_default();
+                        diagnostics.severity(&rule_id, &severity_raw);
                         current_rule = Some(Rule {
                             id: rule_id,
-                            stig_id: current_group_id.clone(),
+                            uuid: None,
+                            stig_id: String::new(),
                             group_id: current_group_id.clone(),
+                            group_title: current_group_title.clone(),
+                            srg_id: current_group_title
+                                .starts_with("SRG-")
+                                .then(|| current_group_title.clone()),
+                            group_description: (!current_group_description.is_empty())
+                                .then(|| current_group_description.clone()),
                             title: String::new(),
                             severity: map_severity(&severity_raw).to_string(),
+                            weight: attr_value(e.as_ref(), "weight"),
                             description: String::new(),
                             fix_text: String::new(),
+                            fix_ref: None,
+                            fix_id: None,
                             check_text: String::new(),
+                            check_system: None,
+                            check_content_ref: None,
+                            false_positives: None,
+                            false_negatives: None,
+                            documentable: None,
+                            mitigations: None,
+                            severity_override_guidance: None,
+                            potential_impacts: None,
+                            third_party_tools: None,
+                            mitigation_control: None,
+                            responsibility: None,
+                            ia_controls: None,
                             cci_ids: Vec::new(),
+                            legacy_ids: Vec::new(),
+                            idents: Vec::new(),
+                            references: Vec::new(),
                             status: "not_reviewed".to_string(),
                             finding_details: String::new(),
                             comments: String::new(),
+                            severity_override: None,
+                            severity_justification: None,
                         });
                     }
-                    _ => {}
+                    "ident" if in_rule => {
+                        current_ident_system =
+                            attr_value(e.as_ref(), "system").unwrap_or_default();
+                    }
+                    "reference" if in_rule && depth == rule_depth + 1 => {
+                        current_reference = Some(Reference::default());
+                    }
+                    local => {
+                        if let Some(rule) = current_rule.as_mut() {
+                            apply_rule_attrs(rule, local, e.as_ref());
+                        }
+                    }
                 }
-                current_tag = local;
             }
             Ok(Event::End(ref e)) => {
                 let local = String::from_utf8_lossy(e.local_name().as_ref()).to_string();
+                let depth = path.len();
                 if local == "Rule" && in_rule && depth == rule_depth {
-                    if let Some(rule) = current_rule.take() {
+                    if let Some(mut rule) = current_rule.take() {
+                        // Older benchmarks omit <version>; fall back to the V-number
+                        if rule.stig_id.is_empty() {
+                            rule.stig_id = rule.group_id.clone();
+                        }
+                        diagnostics.rule(&rule);
                         rules.push(rule);
                     }
                     in_rule = false;
                 }
+                if local == "reference" && in_rule && depth == rule_depth + 1 {
+                    if let (Some(reference), Some(rule)) =
+                        (current_reference.take(), current_rule.as_mut())
+                    {
+                        rule.references.push(reference);
+                    }
+                }
+                if local == "Profile" {
+                    if let Some(profile) = current_profile.take() {
+                        profiles.push(profile);
+                    }
+                }
                 if local == "Group" {
                     in_group = false;
                 }
-                depth = depth.saturating_sub(1);
-                current_tag.clear();
+                if local == "Benchmark" {
+                    in_benchmark = false;
+                }
+                path.pop();
+            }
+            Ok(Event::Empty(ref e)) => {
+                let local = String::from_utf8_lossy(e.local_name().as_ref()).to_string();
+                match local.as_str() {
+                    // <select idref=… selected=…/> is almost always self-closing
+                    "select" => {
+                        if let (Some(profile), Some(idref)) =
+                            (current_profile.as_mut(), selected_idref(e.as_ref()))
+                        {
+                            profile.selected.push(idref);
+                        }
+                    }
+                    "platform" if in_benchmark && path.len() == benchmark_depth => {
+                        if let Some(idref) = attr_value(e.as_ref(), "idref") {
+                            platforms.push(idref);
+                        }
+                    }
+                    "notice" if in_benchmark && path.len() == benchmark_depth => {
+                        notices.push(TextBlock {
+                            id: attr_value(e.as_ref(), "id").unwrap_or_default(),
+                            text: String::new(),
+                        });
+                    }
+                    _ => {
+                        if let Some(rule) = current_rule.as_mut() {
+                            apply_rule_attrs(rule, &local, e.as_ref());
+                        }
+                    }
+                }
             }
             Ok(Event::Text(ref e)) => {
                 let text = e.unescape().unwrap_or_default().trim().to_string();
                 if text.is_empty() {
                     continue;
                 }
+                // Only direct children count: a <dc:title> inside <reference>
+                // must not be mistaken for the Rule or Benchmark title.
+                let (parent, tag) = match path.as_slice() {
+                    [.., parent, tag] => (parent.as_str(), tag.as_str()),
+                    _ => continue,
+                };
                 if in_rule {
-                    if let Some(ref mut rule) = current_rule {
-                        match current_tag.as_str() {
-                            "title" => rule.title = text,
-                            "description" => {
-                                rule.description = clean_description(&text)
+                    if let Some(ref mut reference) = current_reference {
+                        match tag {
+                            "publisher" => reference.publisher = text,
+                            "type" => reference.ref_type = text,
+                            "subject" => reference.subject = text,
+                            "identifier" => reference.identifier = text,
+                            _ => {}
+                        }
+                    } else if let Some(ref mut rule) = current_rule {
+                        match (parent, tag) {
+                            ("Rule", "title") => rule.title = text,
+                            ("Rule", "version") => rule.stig_id = text,
+                            ("Rule", "description") => {
+                                rule.description = clean_description(&text);
+                                apply_discussion_fields(rule, &text);
+                            }
+                            ("Rule", "fixtext" | "fix-text") => {
+                                rule.fix_text = normalize_block(&text)
+                            }
+                            ("check", "check-content") => rule.check_text = normalize_block(&text),
+                            ("Rule", "ident") => {
+                                push_ident(rule, &current_ident_system, text)
                             }
-                            "fixtext" | "fix-text" => rule.fix_text = text,
-                            "check-content" => rule.check_text = text,
-                            "ident" => rule.cci_ids.push(text),
                             _ => {}
                         }
                     }
-                } else if in_benchmark {
-                    match current_tag.as_str() {
-                        "title" if title.is_empty() => title = text,
-                        "description" if description.is_empty() => {
+                } else if let Some(ref mut profile) = current_profile {
+                    match (parent, tag) {
+                        ("Profile", "title") => profile.title = text,
+                        ("Profile", "description") => {
+                            profile.description = clean_description(&text)
+                        }
+                        _ => {}
+                    }
+                } else if in_group && parent == "Group" {
+                    match tag {
+                        "title" => current_group_title = text,
+                        "description" => current_group_description = clean_description(&text),
+                        _ => {}
+                    }
+                } else if in_benchmark && path.len() > benchmark_depth {
+                    // Front matter and notices may wrap their text in XHTML,
+                    // so go by the Benchmark child the text sits under.
+                    let direct = parent == "Benchmark";
+                    match path[benchmark_depth].as_str() {
+                        "title" if direct && title.is_empty() => title = text,
+                        "description" if direct && description.is_empty() => {
                             description = clean_description(&text)
                         }
-                        "version" if version.is_empty() => version = text,
-                        "plain-text" | "release-info" if release_info.is_empty() => {
+                        "version" if direct && version.is_empty() => version = text,
+                        "status" if direct => status = text,
+                        "release-info" if direct && release_info.is_empty() => {
                             release_info = text
                         }
+                        "plain-text" if direct => {
+                            if let Some(block) = plain_texts.last_mut() {
+                                push_line(&mut block.text, &text);
+                            }
+                        }
+                        "notice" => {
+                            if let Some(block) = notices.last_mut() {
+                                push_line(&mut block.text, &text);
+                            }
+                        }
+                        "front-matter" => push_line(&mut front_matter, &text),
+                        "rear-matter" => push_line(&mut rear_matter, &text),
+                        "reference" => {
+                            if let Some(reference) = references.last_mut() {
+                                match tag {
+                                    "publisher" => reference.publisher = text,
+                                    "source" => reference.source = text,
+                                    _ => {}
+                                }
+                            }
+                        }
                         _ => {}
                     }
                 }
@@ -238,212 +837,976 @@ pub fn parse_xccdf(xml: &str) -> Result<StigData> {
         buf.clear();
     }
 
+    if !seen_benchmark {
+        bail!("No Benchmark element found in XCCDF");
+    }
+
     if title.is_empty() {
         title = "Unknown STIG".to_string();
+        diagnostics.unknown_title();
     } else if let Some(rest) = title.strip_prefix("DPMS Target ") {
         title = rest.to_string();
     }
 
+    // DISA tags the release line `<plain-text id="release-info">`; older
+    // benchmarks carry a single untagged plain-text
+    if release_info.is_empty() {
+        release_info = plain_texts
+            .iter()
+            .find(|block| block.id == "release-info")
+            .or_else(|| plain_texts.first())
+            .map(|block| block.text.clone())
+            .unwrap_or_default();
+    }
+
     Ok(StigData {
+        benchmark_id,
         title,
         description,
         version,
         release_info,
+        status,
+        status_date,
+        notices,
+        front_matter: (!front_matter.is_empty()).then_some(front_matter),
+        rear_matter: (!rear_matter.is_empty()).then_some(rear_matter),
+        references,
+        platforms,
+        plain_texts,
+        profiles,
         rules,
+        warnings: diagnostics.finish(),
+        parser_version: Some(PARSER_VERSION),
     })
 }
 
-/// Find and return the text content of the XCCDF file within a ZIP archive.
-pub fn extract_xccdf_from_zip(zip_bytes: &[u8]) -> Result<String> {
-    use std::io::Read;
-    let cursor = std::io::Cursor::new(zip_bytes);
-    let mut archive = zip::ZipArchive::new(cursor)?;
+/// One XCCDF document found inside a STIG ZIP, already parsed.
+pub struct XccdfFile {
+    /// Path of the entry through any nested archives (see
+    /// [`archive::ArchiveEntry::display_path`]).
+    pub name: String,
+    /// True for SCAP benchmarks (`*_Benchmark.xml` data streams and
+    /// `*Benchmark-xccdf.xml`), false for the manual STIG XCCDF.
+    pub scap: bool,
+    pub stig: Result<StigData>,
+}
 
-    // STIGs may be double-zipped; handle one level of nesting
-    for i in 0..archive.len() {
-        let mut file = archive.by_index(i)?;
-        let name = file.name().to_lowercase();
+/// Everything found in a STIG package.
+pub struct PackageScan {
+    pub files: Vec<XccdfFile>,
+    /// Entries passed over (READMEs, overview PDFs, …), and why.
+    pub skipped: Vec<SkippedEntry>,
+}
 
-        // Direct XCCDF XML inside the outer ZIP
-        if name.ends_with("_xccdf.xml") || name.ends_with("-xccdf.xml") {
-            let mut content = String::new();
-            file.read_to_string(&mut content)?;
-            return Ok(content);
+/// Find and parse every XCCDF file within a ZIP archive.
+///
+/// Several DISA packages (Cisco IOS NDM/RTR/L2S, Juniper SRX ALG/NDM/VPN) ship
+/// more than one benchmark, so all of them are returned in archive order.
+/// SCAP benchmark data streams are returned too, flagged with `scap`.
+/// Each benchmark is parsed while it is decompressed; a parse failure is kept
+/// on its `XccdfFile` rather than failing the whole archive.  Nested ZIPs are
+/// searched up to `limits.max_depth` deep; breaking one of `limits` fails the
+/// archive with a [`archive::ZipLimitError`].  Every other entry is listed in
+/// `skipped`.
+pub fn extract_xccdf_from_zip(zip_bytes: &[u8], limits: ZipLimits) -> Result<PackageScan> {
+    let mut found = Vec::new();
+    let mut skipped = Vec::new();
+    for item in walk_archive(std::io::Cursor::new(zip_bytes), limits, true)? {
+        match item {
+            Walked::Xccdf { entry, stig } => found.push(XccdfFile {
+                name: entry.display_path(),
+                scap: entry.kind == EntryKind::ScapBenchmark,
+                stig: *stig,
+            }),
+            Walked::Rejected { error, .. } => return Err(error.into()),
+            Walked::Skipped { entry, reason } => skipped.push(SkippedEntry::new(entry, reason)),
         }
+    }
+    if found.is_empty() {
+        bail!("No *_xccdf.xml found in ZIP archive");
+    }
+    Ok(PackageScan { files: found, skipped })
+}
 
-        // Inner ZIP — extract it and recurse one level
-        if name.ends_with(".zip") {
-            let mut inner_bytes = Vec::new();
-            file.read_to_end(&mut inner_bytes)?;
-            if let Ok(xccdf) = extract_xccdf_from_zip(&inner_bytes) {
-                return Ok(xccdf);
-            }
-        }
+/// Assign a catalog ID to each benchmark extracted from one archive.
+///
+/// A lone benchmark keeps `base_id`. When there are several, each gets
+/// `base_id-<suffix>`, where the suffix is the part of its filename that
+/// differs from its siblings:
+///
+/// `U_Cisco_IOS_XE_Router_NDM_STIG_V3R4_Manual-xccdf.xml` → `cisco-ios-xe-router-ndm`
+/// `U_Cisco_IOS_XE_Router_RTR_STIG_V3R2_Manual-xccdf.xml` → `cisco-ios-xe-router-rtr`
+pub fn benchmark_ids(base_id: &str, files: &[XccdfFile]) -> Vec<String> {
+    if files.len() <= 1 {
+        return vec![base_id.to_string(); files.len()];
     }
 
-    bail!("No *_xccdf.xml found in ZIP archive")
+    let token_lists: Vec<Vec<String>> = files
+        .iter()
+        .map(|f| {
+            let base = f.name.rsplit('/').next().unwrap_or(&f.name);
+            let lower = base.to_ascii_lowercase();
+            let stem_len = [
+                "_manual-xccdf.xml",
+                "_benchmark-xccdf.xml",
+                "_benchmark.xml",
+                "-xccdf.xml",
+                "_xccdf.xml",
+            ]
+                .iter()
+                .find(|sfx| lower.ends_with(*sfx))
+                .map_or(base.len(), |sfx| base.len() - sfx.len());
+            filename_to_id(&base[..stem_len])
+                .split('-')
+                .map(str::to_string)
+                .collect()
+        })
+        .collect();
+
+    // Drop the leading and trailing tokens every filename shares
+    let shortest = token_lists.iter().map(Vec::len).min().unwrap_or(0);
+    let prefix = (0..shortest)
+        .take_while(|&i| token_lists.iter().all(|t| t[i] == token_lists[0][i]))
+        .count();
+    let suffix = (0..shortest - prefix)
+        .take_while(|&i| {
+            let last = &token_lists[0][token_lists[0].len() - 1 - i];
+            token_lists.iter().all(|t| &t[t.len() - 1 - i] == last)
+        })
+        .count();
+
+    let mut ids: Vec<String> = Vec::with_capacity(files.len());
+    for (n, tokens) in token_lists.iter().enumerate() {
+        let distinct = tokens[prefix..tokens.len() - suffix].join("-");
+        let mut id = if distinct.is_empty() {
+            format!("{base_id}-{}", n + 1)
+        } else {
+            format!("{base_id}-{distinct}")
+        };
+        if ids.contains(&id) {
+            id = format!("{id}-{}", n + 1);
+        }
+        ids.push(id);
+    }
+    ids
 }
 
-// ── Helpers re-used by child_text (kept for future use) ─────────────────────
-#[allow(dead_code)]
-pub fn child_text_pub(parent_bytes: &[u8], tag: &str) -> Option<String> {
-    child_text(parent_bytes, tag)
+/// Pair each file from one package with its catalog ID.
+///
+/// Manual and SCAP benchmarks are numbered separately so that a SCAP benchmark
+/// shipped next to its manual STIG resolves to the same ID.
+pub fn package_ids(base_id: &str, files: Vec<XccdfFile>) -> Vec<(String, XccdfFile)> {
+    let (scap, manual): (Vec<XccdfFile>, Vec<XccdfFile>) =
+        files.into_iter().partition(|f| f.scap);
+    let manual_ids = benchmark_ids(base_id, &manual);
+    let scap_ids = benchmark_ids(base_id, &scap);
+    manual_ids
+        .into_iter()
+        .zip(manual)
+        .chain(scap_ids.into_iter().zip(scap))
+        .collect()
 }
 
 // ── Library bulk extraction ───────────────────────────────────────────────────
 
+/// Everything found in a library bundle.
+#[derive(Default)]
+pub struct LibraryScan {
+    /// Benchmarks ready to write to disk and upsert into the catalog.
+    pub entries: Vec<LibraryEntry>,
+    pub errors: Vec<LibraryError>,
+    /// Entries passed over, and why.
+    pub skipped: Vec<SkippedEntry>,
+}
+
 /// One successfully parsed STIG from a library bundle.
 pub struct LibraryEntry {
     pub id: String,
-    pub category: String,
+    /// ID of the package as a whole; differs from `id` when the package holds
+    /// several benchmarks.
+    pub package_id: String,
+    /// Where in the bundle it was found.
+    pub path: String,
+    /// Parsed from a SCAP benchmark rather than the manual XCCDF.
+    pub scap: bool,
     pub stig: StigData,
 }
 
+/// A library bundle entry that could not be imported.
+pub struct LibraryError {
+    pub id: String,
+    pub path: String,
+    /// `extraction_failed`, `parse_failed`, or a [`archive::ZipLimitError::kind`].
+    pub kind: &'static str,
+    pub message: String,
+}
+
+/// An archive entry that is not a benchmark, or could not be read.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedEntry {
+    pub path: String,
+    pub kind: EntryKind,
+    pub reason: String,
+}
+
+impl SkippedEntry {
+    fn new(entry: archive::ArchiveEntry, reason: String) -> Self {
+        Self { path: entry.display_path(), kind: entry.kind, reason }
+    }
+}
+
 /// Derive a stable slug ID from a DISA ZIP filename.
 ///
-/// `U_MS_Windows_11_V2R3_STIG.zip` → `ms-windows-11`
-/// `U_RHEL_9_V2R2_STIG.zip`        → `rhel-9`
+/// `U_MS_Windows_11_V2R3_STIG.zip`             → `ms-windows-11`
+/// `U_RHEL_9_V2R2_STIG.zip`                    → `rhel-9`
+/// `U_Cisco_ASA_Y26M01_STIG.zip`               → `cisco-asa`
+/// `U_MS_Windows_11_V2R3_STIG_SCAP_1-3_Benchmark.zip` → `ms-windows-11`
+/// `U_Kubernetes_STIG_V2R5_Manual-xccdf.xml`   → `kubernetes`
 pub fn filename_to_id(zip_name: &str) -> String {
     // Use just the basename (strip any directory prefix from ZIP entry paths)
     let base = zip_name.rsplit('/').next().unwrap_or(zip_name);
 
-    // Strip extension, U_ prefix, _STIG suffix
-    let base = base.strip_suffix(".zip").unwrap_or(base);
-    let base = base.strip_suffix(".ZIP").unwrap_or(base);
+    // Strip extension and U_ prefix; release_independent_id does the rest
+    let base = [".zip", ".ZIP", ".xml", ".XML"]
+        .iter()
+        .find_map(|ext| base.strip_suffix(ext))
+        .unwrap_or(base);
     let base = base.strip_prefix("U_").unwrap_or(base);
-    let base = base.strip_suffix("_STIG").unwrap_or(base);
-
-    // Strip trailing version marker _V<n>R<n>
-    let base = {
-        let bytes = base.as_bytes();
-        let mut cut = base.len();
-        for i in (0..bytes.len().saturating_sub(3)).rev() {
-            if bytes[i] == b'_'
-                && bytes[i + 1] == b'V'
-                && bytes[i + 2].is_ascii_digit()
-            {
-                cut = i;
-                break;
-            }
-        }
-        &base[..cut]
-    };
 
-    // Slugify: lowercase, non-alphanum → hyphen, collapse repeated hyphens
-    let raw: String = base
-        .chars()
-        .map(|c| if c.is_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+    release_independent_id(base)
+}
+
+/// Slugify a DISA package name, dropping every release marker, so that each
+/// quarterly release of a product maps to the same catalog ID.
+///
+/// Release markers are `V#R#` and `Y##M##` anywhere in the name, and a
+/// trailing `_STIG`, `_Manual`, `_Benchmark` or `_SCAP_1-3`.  Existing IDs
+/// pass through unchanged apart from their markers, so the function also
+/// normalizes IDs already in the catalog: `cisco-asa-y26m01` → `cisco-asa`.
+pub fn release_independent_id(name: &str) -> String {
+    // Slugify: lowercase, split on anything not alphanumeric, rejoin with hyphens
+    let lower = name.to_lowercase();
+    let mut tokens: Vec<&str> = lower
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty() && !is_release_marker(t))
         .collect();
 
-    let mut slug = String::with_capacity(raw.len());
-    let mut prev_dash = false;
-    for c in raw.chars() {
-        if c == '-' {
-            if !prev_dash && !slug.is_empty() {
-                slug.push('-');
+    let is_number = |t: &str| t.chars().all(|c| c.is_ascii_digit());
+    loop {
+        match tokens.as_slice() {
+            [.., "stig" | "manual" | "benchmark" | "xccdf"] | [.., "scap"] => {
+                tokens.pop();
             }
-            prev_dash = true;
-        } else {
-            slug.push(c);
-            prev_dash = false;
+            [.., "scap", major, minor] if is_number(major) && is_number(minor) => {
+                tokens.truncate(tokens.len() - 3);
+            }
+            _ => break,
         }
     }
-    slug.trim_end_matches('-').to_string()
-}
-
-/// Infer the catalog category from an XCCDF benchmark title.
-pub fn infer_category(title: &str) -> &'static str {
-    let t = title.to_lowercase();
-    if t.contains("windows") {
-        "Windows"
-    } else if t.contains("red hat")
-        || t.contains("rhel")
-        || t.contains("ubuntu")
-        || t.contains("linux")
-        || t.contains("debian")
-        || t.contains("suse")
-        || t.contains("alma")
-        || t.contains("rocky")
-    {
-        "Linux"
-    } else if t.contains("chrome")
-        || t.contains("firefox")
-        || t.contains("edge")
-        || t.contains("safari")
-        || t.contains("browser")
-    {
-        "Browser"
-    } else {
-        "Network"
-    }
-}
-
-/// Process every STIG ZIP inside a DISA SRG/STIG library bundle.
+    tokens.join("-")
+}
+
+/// `v2r3` or `y25m04`.
+fn is_release_marker(token: &str) -> bool {
+    let numbered = |rest: &str, separator: char| {
+        rest.split_once(separator).is_some_and(|(a, b)| {
+            !a.is_empty()
+                && !b.is_empty()
+                && a.chars().all(|c| c.is_ascii_digit())
+                && b.chars().all(|c| c.is_ascii_digit())
+        })
+    };
+    token.strip_prefix('v').is_some_and(|rest| numbered(rest, 'r'))
+        || token.strip_prefix('y').is_some_and(|rest| rest.len() == 5 && numbered(rest, 'm'))
+}
+
+/// Process every STIG inside a DISA SRG/STIG library bundle.
+///
+/// The bundle is walked with [`walk_archive`], so STIGs are found however
+/// deeply they are nested (up to `limits.max_depth`), including XCCDFs placed
+/// directly in the bundle.  SRGs, overview PDFs and other files are listed in
+/// `skipped` with the reason.  A package holding several benchmarks produces
+/// one entry per benchmark (see [`benchmark_ids`]), each identified by the
+/// package it came in (see [`package_index`]).
 ///
-/// Returns `(entries, errors)` where entries are ready to write to disk and
-/// upsert into the catalog.  Only ZIP entries whose filename ends with
-/// `_STIG.zip` (case-insensitive) are processed; SRG ZIPs and other files
-/// are silently skipped.
+/// The bundle is read through `library` (typically the uploaded file on disk),
+/// and each benchmark is parsed as it is decompressed, so only one inner STIG
+/// ZIP is held in memory at a time.  `limits` apply to the bundle as a whole;
+/// an entry that breaks the per-entry limits is reported and skipped, while
+/// exhausting the bundle's total size or entry count ends the import.
 ///
 /// **Note:** this function is CPU-bound and should be called from
 /// `tokio::task::spawn_blocking`.
-pub fn extract_all_from_library(
-    library_bytes: &[u8],
-) -> (Vec<LibraryEntry>, Vec<(String, String)>) {
-    use std::io::Read;
-
-    let cursor = std::io::Cursor::new(library_bytes);
-    let mut archive = match zip::ZipArchive::new(cursor) {
-        Ok(a) => a,
-        Err(e) => return (vec![], vec![("(outer zip)".into(), e.to_string())]),
+pub fn extract_all_from_library<R: Read + Seek>(library: R, limits: ZipLimits) -> LibraryScan {
+    let mut scan = LibraryScan::default();
+    let walked = match walk_archive(library, limits, false) {
+        Ok(walked) => walked,
+        Err(e) => {
+            scan.errors.push(LibraryError {
+                id: "(outer zip)".into(),
+                path: String::new(),
+                kind: archive::error_kind(&e, "extraction_failed"),
+                message: format!("ZIP extraction failed: {e}"),
+            });
+            return scan;
+        }
     };
 
-    let mut entries: Vec<LibraryEntry> = Vec::new();
-    let mut errors: Vec<(String, String)> = Vec::new();
-
-    for i in 0..archive.len() {
-        // Read each ZIP entry name + bytes (separate scope to appease borrow checker)
-        let (raw_name, inner_bytes) = {
-            let mut file = match archive.by_index(i) {
-                Ok(f) => f,
-                Err(_) => continue,
-            };
-            let raw_name = file.name().to_string();
-
-            // Only process STIG ZIPs; skip SRGs, READMEs, manifests, etc.
-            if !raw_name.to_lowercase().ends_with("_stig.zip") {
-                continue;
+    // Group benchmarks by the package they came in, in bundle order
+    let mut packages: Vec<(Vec<String>, Vec<XccdfFile>)> = Vec::new();
+    for item in walked {
+        match item {
+            Walked::Xccdf { entry, stig } => {
+                let package = entry.path[..=package_index(&entry.path)].to_vec();
+                let file = XccdfFile {
+                    name: entry.display_path(),
+                    scap: entry.kind == EntryKind::ScapBenchmark,
+                    stig: *stig,
+                };
+                match packages.iter_mut().find(|(p, _)| *p == package) {
+                    Some((_, files)) => files.push(file),
+                    None => packages.push((package, vec![file])),
+                }
+            }
+            Walked::Rejected { entry, error } => scan.errors.push(LibraryError {
+                id: filename_to_id(&entry.path[package_index(&entry.path)]),
+                path: entry.display_path(),
+                kind: error.kind(),
+                message: format!("ZIP rejected: {error}"),
+            }),
+            Walked::Skipped { entry, reason } => {
+                scan.skipped.push(SkippedEntry::new(entry, reason))
             }
+        }
+    }
 
-            let mut bytes = Vec::new();
-            if file.read_to_end(&mut bytes).is_err() {
-                errors.push((filename_to_id(&raw_name), "failed to read ZIP entry".into()));
-                continue;
+    for (package, files) in packages {
+        let base_id = filename_to_id(package.last().map_or("", String::as_str));
+        for (id, file) in package_ids(&base_id, files) {
+            match file.stig {
+                Ok(stig) => scan.entries.push(LibraryEntry {
+                    id,
+                    package_id: base_id.clone(),
+                    path: file.name,
+                    scap: file.scap,
+                    stig,
+                }),
+                Err(e) => scan.errors.push(LibraryError {
+                    id,
+                    path: file.name,
+                    kind: "parse_failed",
+                    message: format!("XCCDF parse failed: {e}"),
+                }),
             }
-            (raw_name, bytes)
+        }
+    }
+
+    scan
+}
+
+/// Index in `path` of the package an entry was shipped in, which names its
+/// catalog ID: the outermost enclosing `*_STIG.zip` or `*_Benchmark.zip`,
+/// else the innermost enclosing archive, else the entry itself.
+///
+/// `U_Cisco_Router.zip!/U_Cisco_IOS_XE_Router_Y25M04_STIG.zip!/…-xccdf.xml` → 1
+fn package_index(path: &[String]) -> usize {
+    let archives = &path[..path.len().saturating_sub(1)];
+    archives
+        .iter()
+        .position(|name| {
+            let lower = name.to_lowercase();
+            lower.ends_with("_stig.zip") || lower.ends_with("_benchmark.zip")
+        })
+        .or(archives.len().checked_sub(1))
+        .unwrap_or(path.len().saturating_sub(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A two-rule benchmark in the shape DISA publishes.
+    const XCCDF: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<Benchmark xmlns="http://checklists.nist.gov/xccdf/1.1"
+           xmlns:dc="http://purl.org/dc/elements/1.1/" id="Kubernetes_STIG">
+  <title>Kubernetes Security Technical Implementation Guide</title>
+  <plain-text id="release-info">Release: 5 Benchmark Date: 05 Jan 2026</plain-text>
+  <version>2</version>
+  <Group id="V-242376">
+    <title>SRG-APP-000014-CTR-000035</title>
+    <Rule id="SV-242376r960759_rule" severity="medium">
+      <version>CNTR-K8-000150</version>
+      <title>The Kubernetes Controller Manager must use TLS 1.2, at a minimum.</title>
+      <reference>
+        <dc:title>DPMS Target Kubernetes</dc:title>
+        <dc:publisher>DISA</dc:publisher>
+        <dc:type>DPMS Target</dc:type>
+        <dc:subject>Kubernetes</dc:subject>
+        <dc:identifier>5289</dc:identifier>
+      </reference>
+      <ident system="http://cyber.mil/cci">CCI-000068</ident>
+      <fixtext fixref="F-45651r863736_fix">Set "--tls-min-version".</fixtext>
+      <check system="C-45651r863735_chk"><check-content>Run the command.</check-content></check>
+    </Rule>
+  </Group>
+  <Group id="V-242377">
+    <title>SRG-APP-000014-CTR-000040</title>
+    <Rule id="SV-242377r960759_rule" severity="high">
+      <title>The Kubernetes Scheduler must use TLS 1.2.</title>
+      <ident system="http://cyber.mil/cci">CCI-000068</ident>
+      <fixtext fixref="F-45652r863739_fix">Fix it.</fixtext>
+      <check system="C-45652r863738_chk"><check-content>Check it.</check-content></check>
+    </Rule>
+  </Group>
+</Benchmark>"#;
+
+    fn parse(xml: &str) -> StigData {
+        parse_xccdf_reader(xml.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn stig_id_comes_from_rule_version() {
+        let stig = parse(XCCDF);
+        assert_eq!(stig.rules[0].stig_id, "CNTR-K8-000150");
+        assert_eq!(stig.rules[0].group_id, "V-242376");
+    }
+
+    #[test]
+    fn stig_id_falls_back_to_group_id_without_version() {
+        let stig = parse(XCCDF);
+        assert_eq!(stig.rules[1].stig_id, "V-242377");
+        assert_eq!(stig.rules[1].group_id, "V-242377");
+    }
+
+    #[test]
+    fn reference_title_does_not_replace_rule_title() {
+        let stig = parse(XCCDF);
+        assert_eq!(stig.title, "Kubernetes Security Technical Implementation Guide");
+        assert_eq!(
+            stig.rules[0].title,
+            "The Kubernetes Controller Manager must use TLS 1.2, at a minimum."
+        );
+        assert_eq!(stig.rules[1].title, "The Kubernetes Scheduler must use TLS 1.2.");
+    }
+
+    #[test]
+    fn rule_references_are_structured() {
+        let stig = parse(XCCDF);
+        let [reference] = &stig.rules[0].references[..] else {
+            panic!("expected one reference, got {:?}", stig.rules[0].references);
+        };
+        assert_eq!(reference.publisher, "DISA");
+        assert_eq!(reference.ref_type, "DPMS Target");
+        assert_eq!(reference.subject, "Kubernetes");
+        assert_eq!(reference.identifier, "5289");
+        assert!(stig.rules[1].references.is_empty());
+    }
+
+    /// Parse a benchmark holding one Group whose Rule has the given children.
+    fn parse_rule(children: &str) -> Rule {
+        let xml = format!(
+            r#"<Benchmark xmlns="http://checklists.nist.gov/xccdf/1.1" id="Test_STIG">
+  <title>Test STIG</title>
+  <Group id="V-1">
+    <title>SRG-APP-000001</title>
+    <Rule id="SV-1r1_rule" severity="medium">
+      <title>Rule</title>
+      {children}
+    </Rule>
+  </Group>
+</Benchmark>"#
+        );
+        parse(&xml).rules.remove(0)
+    }
+
+    #[test]
+    fn idents_are_filed_by_system() {
+        let rule = parse_rule(
+            r#"<ident system="http://cyber.mil/cci">CCI-000068</ident>
+      <ident system="http://cyber.mil/legacy">V-63319</ident>
+      <ident system="http://iase.disa.mil/legacy/">SV-77809</ident>
+      <ident system="https://example.com/cve">CVE-2024-0001</ident>
+      <ident>CCI-000366</ident>"#,
+        );
+        assert_eq!(rule.cci_ids, ["CCI-000068", "CCI-000366"]);
+        assert_eq!(rule.legacy_ids, ["V-63319", "SV-77809"]);
+        let [ident] = &rule.idents[..] else {
+            panic!("expected one other ident, got {:?}", rule.idents);
         };
+        assert_eq!(ident.system, "https://example.com/cve");
+        assert_eq!(ident.value, "CVE-2024-0001");
+    }
 
-        let id = filename_to_id(&raw_name);
+    #[test]
+    fn discussion_fields_are_split_out_of_the_description() {
+        let rule = parse_rule(
+            "<description>&lt;VulnDiscussion&gt;Weak ciphers expose traffic.\
+&lt;/VulnDiscussion&gt;&lt;FalsePositives&gt;None known.&lt;/FalsePositives&gt;\
+&lt;FalseNegatives&gt;&lt;/FalseNegatives&gt;&lt;Documentable&gt;false&lt;/Documentable&gt;\
+&lt;Mitigations&gt;Use a proxy.&lt;/Mitigations&gt;\
+&lt;SeverityOverrideGuidance&gt;CAT III if isolated.&lt;/SeverityOverrideGuidance&gt;\
+&lt;PotentialImpacts&gt;Clients may fail.&lt;/PotentialImpacts&gt;\
+&lt;ThirdPartyTools&gt;nmap&lt;/ThirdPartyTools&gt;\
+&lt;MitigationControl&gt;SC-8&lt;/MitigationControl&gt;\
+&lt;Responsibility&gt;System Administrator&lt;/Responsibility&gt;\
+&lt;Responsibility&gt;Information Assurance Officer&lt;/Responsibility&gt;\
+&lt;IAControls&gt;ECSC-1&lt;/IAControls&gt;</description>",
+        );
+        assert_eq!(rule.description, "Weak ciphers expose traffic.");
+        assert_eq!(rule.false_positives.as_deref(), Some("None known."));
+        assert_eq!(rule.false_negatives, None);
+        assert_eq!(rule.documentable.as_deref(), Some("false"));
+        assert_eq!(rule.mitigations.as_deref(), Some("Use a proxy."));
+        assert_eq!(rule.severity_override_guidance.as_deref(), Some("CAT III if isolated."));
+        assert_eq!(rule.potential_impacts.as_deref(), Some("Clients may fail."));
+        assert_eq!(rule.third_party_tools.as_deref(), Some("nmap"));
+        assert_eq!(rule.mitigation_control.as_deref(), Some("SC-8"));
+        assert_eq!(
+            rule.responsibility.as_deref(),
+            Some("System Administrator\nInformation Assurance Officer")
+        );
+        assert_eq!(rule.ia_controls.as_deref(), Some("ECSC-1"));
+    }
 
-        let xccdf = match extract_xccdf_from_zip(&inner_bytes) {
-            Ok(x) => x,
-            Err(e) => {
-                errors.push((id, format!("ZIP extraction failed: {e}")));
-                continue;
-            }
+    /// [`XCCDF`] with one profile selecting by Group id and deselecting the
+    /// other Group, and one selecting by (XCCDF 1.2) Rule id.
+    fn with_profiles() -> String {
+        XCCDF.replacen(
+            "<version>2</version>",
+            r#"<version>2</version>
+  <Profile id="MAC-1_Classified">
+    <title>I - Mission Critical Classified</title>
+    <description>Classified systems</description>
+    <select idref="V-242376" selected="true"/>
+    <select idref="V-242377" selected="false"/>
+  </Profile>
+  <Profile id="xccdf_mil.disa.stig_profile_MAC-3_Public">
+    <title>III - Administrative Public</title>
+    <select idref="xccdf_mil.disa.stig_rule_SV-242377r960759_rule" selected="1"></select>
+  </Profile>"#,
+            1,
+        )
+    }
+
+    #[test]
+    fn profiles_list_selected_rules_only() {
+        let stig = parse(&with_profiles());
+        let [classified, public] = &stig.profiles[..] else {
+            panic!("expected two profiles, got {:?}", stig.profiles);
         };
+        assert_eq!(classified.id, "MAC-1_Classified");
+        assert_eq!(classified.title, "I - Mission Critical Classified");
+        assert_eq!(classified.description, "Classified systems");
+        assert_eq!(classified.selected, ["V-242376"]);
+        assert_eq!(public.id, "MAC-3_Public");
+        assert_eq!(public.selected, ["SV-242377r960759_rule"]);
 
-        let stig = match parse_xccdf(&xccdf) {
-            Ok(s) => s,
-            Err(e) => {
-                errors.push((id, format!("XCCDF parse failed: {e}")));
-                continue;
-            }
+        assert!(classified.selects(&stig.rules[0]));
+        assert!(!classified.selects(&stig.rules[1]));
+        assert!(!public.selects(&stig.rules[0]));
+        assert!(public.selects(&stig.rules[1]));
+    }
+
+    #[test]
+    fn check_and_fix_metadata_is_kept() {
+        let rule = parse_rule(
+            r#"<fixtext fixref="F-1r1_fix">Fix it.</fixtext>
+      <fix id="F-1r1_fix"/>
+      <check system="C-1r1_chk">
+        <check-content-ref href="Test_STIG.xml" name="M"/>
+        <check-content>Check it.</check-content>
+      </check>"#,
+        );
+        assert_eq!(rule.check_system.as_deref(), Some("C-1r1_chk"));
+        let content_ref = rule.check_content_ref.expect("check-content-ref");
+        assert_eq!(content_ref.href, "Test_STIG.xml");
+        assert_eq!(content_ref.name, "M");
+        assert_eq!(rule.fix_ref.as_deref(), Some("F-1r1_fix"));
+        assert_eq!(rule.fix_id.as_deref(), Some("F-1r1_fix"));
+        assert_eq!(rule.check_text, "Check it.");
+    }
+
+    #[test]
+    fn rule_weight_is_kept() {
+        let stig = parse(&XCCDF.replacen(
+            r#"severity="medium">"#,
+            r#"severity="medium" weight="10.0">"#,
+            1,
+        ));
+        assert_eq!(stig.rules[0].weight.as_deref(), Some("10.0"));
+        assert_eq!(stig.rules[1].weight, None);
+    }
+
+    #[test]
+    fn rules_carry_their_group_title_and_description() {
+        let xml = XCCDF.replacen(
+            "<title>SRG-APP-000014-CTR-000035</title>",
+            "<title>SRG-APP-000014-CTR-000035</title>
+    <description>&lt;GroupDescription&gt;Transport security&lt;/GroupDescription&gt;</description>",
+            1,
+        )
+        .replacen("SRG-APP-000014-CTR-000040", "Kubernetes scheduler", 1);
+        let stig = parse(&xml);
+
+        let rule = &stig.rules[0];
+        assert_eq!(rule.group_title, "SRG-APP-000014-CTR-000035");
+        assert_eq!(rule.srg_id.as_deref(), Some("SRG-APP-000014-CTR-000035"));
+        assert_eq!(rule.group_description.as_deref(), Some("Transport security"));
+
+        // A Group title that is not an SRG id is kept without an srg_id
+        let rule = &stig.rules[1];
+        assert_eq!(rule.group_title, "Kubernetes scheduler");
+        assert_eq!(rule.srg_id, None);
+        assert_eq!(rule.group_description, None);
+    }
+
+    /// A SCAP 1.3 data stream: an OVAL component ahead of the XCCDF 1.2 one.
+    const DATA_STREAM: &str = r##"<?xml version="1.0" encoding="UTF-8"?>
+<ds:data-stream-collection xmlns:ds="http://scap.nist.gov/schema/scap/source/1.2"
+    id="scap_mil.disa.stig_collection_from_U_Kubernetes_V2R5_STIG_SCAP_1-3_Benchmark.xml">
+  <ds:data-stream id="scap_mil.disa.stig_datastream_U_Kubernetes_V2R5_STIG_SCAP_1-3_Benchmark"/>
+  <ds:component id="scap_mil.disa.stig_comp_U_Kubernetes_V2R5_STIG_SCAP_1-3_Benchmark-oval.xml">
+    <oval_definitions xmlns="http://oval.mitre.org/XMLSchema/oval-definitions-5">
+      <definitions><definition id="oval:mil.disa.stig.k8s:def:1">
+        <metadata><title>OVAL definition</title></metadata>
+      </definition></definitions>
+    </oval_definitions>
+  </ds:component>
+  <ds:component id="scap_mil.disa.stig_comp_U_Kubernetes_V2R5_STIG_SCAP_1-3_Benchmark-xccdf.xml">
+    <Benchmark xmlns="http://checklists.nist.gov/xccdf/1.2"
+               id="xccdf_mil.disa.stig_benchmark_Kubernetes_STIG">
+      <title>Kubernetes Security Technical Implementation Guide</title>
+      <plain-text id="release-info">Release: 5 Benchmark Date: 05 Jan 2026</plain-text>
+      <version>2</version>
+      <Group id="xccdf_mil.disa.stig_group_V-242376">
+        <title>SRG-APP-000014-CTR-000035</title>
+        <Rule id="xccdf_mil.disa.stig_rule_SV-242376r960759_rule" severity="medium">
+          <version>CNTR-K8-000150</version>
+          <title>The Kubernetes Controller Manager must use TLS 1.2, at a minimum.</title>
+          <check system="http://oval.mitre.org/XMLSchema/oval-definitions-5">
+            <check-content-ref href="#scap_mil.disa.stig_comp_U_Kubernetes-oval.xml"
+                               name="oval:mil.disa.stig.k8s:def:1"/>
+          </check>
+        </Rule>
+      </Group>
+    </Benchmark>
+  </ds:component>
+</ds:data-stream-collection>"##;
+
+    #[test]
+    fn data_stream_benchmark_is_read_from_its_component() {
+        let stig = parse(DATA_STREAM);
+        assert_eq!(stig.benchmark_id, "Kubernetes_STIG");
+        assert_eq!(stig.title, "Kubernetes Security Technical Implementation Guide");
+        assert_eq!(stig.version, "2");
+        assert_eq!(stig.release_info, "Release: 5 Benchmark Date: 05 Jan 2026");
+
+        let [rule] = &stig.rules[..] else {
+            panic!("expected one rule, got {}", stig.rules.len());
+        };
+        assert_eq!(rule.id, "SV-242376r960759_rule");
+        assert_eq!(rule.group_id, "V-242376");
+        assert_eq!(rule.stig_id, "CNTR-K8-000150");
+        assert_eq!(
+            rule.check_system.as_deref(),
+            Some("http://oval.mitre.org/XMLSchema/oval-definitions-5")
+        );
+        assert_eq!(
+            rule.check_content_ref.as_ref().map(|r| r.name.as_str()),
+            Some("oval:mil.disa.stig.k8s:def:1")
+        );
+    }
+
+    #[test]
+    fn benchmark_metadata_is_kept() {
+        let xml = XCCDF
+            .replacen(
+                "<title>Kubernetes Security Technical Implementation Guide</title>",
+                r#"<status date="2025-12-10">accepted</status>
+  <title>Kubernetes Security Technical Implementation Guide</title>
+  <notice id="terms-of-use" xml:lang="en-US"/>
+  <front-matter xml:lang="en-US"><p>Read this first.</p></front-matter>
+  <rear-matter xml:lang="en-US">Appendix</rear-matter>
+  <reference href="https://cyber.mil">
+    <dc:publisher>DISA</dc:publisher>
+    <dc:source>STIG.DOD.MIL</dc:source>
+  </reference>"#,
+                1,
+            )
+            .replacen(
+                "<version>2</version>",
+                r#"<plain-text id="generator">DISA STIG Team</plain-text>
+  <platform idref="cpe:/a:kubernetes:kubernetes"/>
+  <version>2</version>"#,
+                1,
+            );
+        let stig = parse(&xml);
+
+        assert_eq!(stig.status, "accepted");
+        assert_eq!(stig.status_date.as_deref(), Some("2025-12-10"));
+        let [notice] = &stig.notices[..] else {
+            panic!("expected one notice, got {:?}", stig.notices);
+        };
+        assert_eq!(notice.id, "terms-of-use");
+        assert_eq!(notice.text, "");
+        assert_eq!(stig.front_matter.as_deref(), Some("Read this first."));
+        assert_eq!(stig.rear_matter.as_deref(), Some("Appendix"));
+
+        let [reference] = &stig.references[..] else {
+            panic!("expected one reference, got {:?}", stig.references);
         };
+        assert_eq!(reference.href.as_deref(), Some("https://cyber.mil"));
+        assert_eq!(reference.publisher, "DISA");
+        assert_eq!(reference.source, "STIG.DOD.MIL");
+
+        assert_eq!(stig.platforms, ["cpe:/a:kubernetes:kubernetes"]);
+        let plain_texts: Vec<(&str, &str)> =
+            stig.plain_texts.iter().map(|b| (b.id.as_str(), b.text.as_str())).collect();
+        assert_eq!(
+            plain_texts,
+            [
+                ("release-info", "Release: 5 Benchmark Date: 05 Jan 2026"),
+                ("generator", "DISA STIG Team"),
+            ]
+        );
+        assert_eq!(stig.release_info, "Release: 5 Benchmark Date: 05 Jan 2026");
+    }
+
+    fn xccdf_file(name: &str) -> XccdfFile {
+        XccdfFile { name: name.to_string(), scap: false, stig: Ok(StigData::default()) }
+    }
+
+    #[test]
+    fn lone_benchmark_keeps_package_id() {
+        let files = [xccdf_file("U_Kubernetes_STIG_V2R5_Manual-xccdf.xml")];
+        assert_eq!(benchmark_ids("kubernetes", &files), ["kubernetes"]);
+        assert!(benchmark_ids("kubernetes", &[]).is_empty());
+    }
+
+    #[test]
+    fn split_package_ids_use_distinct_filename_part() {
+        let files = [
+            xccdf_file("U_Cisco_IOS_XE_Router_NDM_STIG_V3R4_Manual-xccdf.xml"),
+            xccdf_file("nested/U_Cisco_IOS_XE_Router_RTR_STIG_V3R2_Manual-xccdf.xml"),
+        ];
+        assert_eq!(
+            benchmark_ids("cisco-ios-xe-router", &files),
+            ["cisco-ios-xe-router-ndm", "cisco-ios-xe-router-rtr"]
+        );
+    }
+
+    #[test]
+    fn split_package_ids_are_numbered_when_names_match() {
+        let files = [
+            xccdf_file("a/U_Juniper_SRX_STIG_V1R1_Manual-xccdf.xml"),
+            xccdf_file("b/U_Juniper_SRX_STIG_V1R1_Manual-xccdf.xml"),
+        ];
+        assert_eq!(benchmark_ids("juniper-srx", &files), ["juniper-srx-1", "juniper-srx-2"]);
+    }
+
+    #[test]
+    fn short_id_strips_xccdf12_prefix() {
+        assert_eq!(
+            short_id("xccdf_mil.disa.stig_rule_SV-253254r991589_rule".into()),
+            "SV-253254r991589_rule"
+        );
+        assert_eq!(short_id("xccdf_mil.disa.stig_group_V-253254".into()), "V-253254");
+        assert_eq!(
+            short_id("xccdf_mil.disa.stig_profile_MAC-1_Classified".into()),
+            "MAC-1_Classified"
+        );
+        assert_eq!(
+            short_id("xccdf_mil.disa.stig_benchmark_MS_Windows_11_STIG".into()),
+            "MS_Windows_11_STIG"
+        );
+        // Manual benchmark ids and unknown xccdf_ kinds pass through
+        assert_eq!(short_id("SV-253254r991589_rule".into()), "SV-253254r991589_rule");
+        assert_eq!(
+            short_id("xccdf_mil.disa.stig_tailoring_x".into()),
+            "xccdf_mil.disa.stig_tailoring_x"
+        );
+    }
+
+    #[test]
+    fn scap_benchmark_shares_id_with_manual_stig() {
+        let mut scap = xccdf_file("U_MS_Windows_11_V2R3_STIG_SCAP_1-3_Benchmark.xml");
+        scap.scap = true;
+        let files = vec![scap, xccdf_file("U_MS_Windows_11_STIG_V2R3_Manual-xccdf.xml")];
+        let ids: Vec<(String, bool)> = package_ids("ms-windows-11", files)
+            .into_iter()
+            .map(|(id, file)| (id, file.scap))
+            .collect();
+        assert_eq!(
+            ids,
+            [("ms-windows-11".to_string(), false), ("ms-windows-11".to_string(), true)]
+        );
+    }
+
+    #[test]
+    fn release_number_and_benchmark_date_from_release_info() {
+        let info = "Release: 5 Benchmark Date: 05 Jan 2026";
+        assert_eq!(release_number(info), Some(5));
+        assert_eq!(benchmark_date(info), NaiveDate::from_ymd_opt(2026, 1, 5));
+
+        assert_eq!(release_number("Release: 12 Benchmark Date: 24 July 2024"), Some(12));
+        assert_eq!(
+            benchmark_date("Release: 12 Benchmark Date: 24 July 2024"),
+            NaiveDate::from_ymd_opt(2024, 7, 24)
+        );
+        assert_eq!(release_number("Release:3"), Some(3));
+    }
+
+    #[test]
+    fn release_info_without_release_or_date() {
+        assert_eq!(release_number(""), None);
+        assert_eq!(release_number("Release: draft"), None);
+        assert_eq!(benchmark_date("Release: 5"), None);
+        assert_eq!(benchmark_date("Release: 5 Benchmark Date: 05 Jan"), None);
+        assert_eq!(benchmark_date("Release: 5 Benchmark Date: 31 Feb 2026"), None);
+    }
+
+    #[test]
+    fn release_label_needs_numeric_version_and_release() {
+        assert_eq!(release_label("2", Some(5)).as_deref(), Some("V2R5"));
+        assert_eq!(release_label(" 02 ", Some(1)).as_deref(), Some("V2R1"));
+        assert_eq!(release_label("2", None), None);
+        assert_eq!(release_label("2.1", Some(5)), None);
+    }
+
+    #[test]
+    fn normalize_block_keeps_lines_and_paragraphs() {
+        let raw = "Change to the manifests directory. Run the command:\r\n\n      \
+                   grep -i tls-min-version *   \n\n\n      \
+                   If it is not set, this is a finding.\n    ";
+        assert_eq!(
+            normalize_block(raw),
+            "Change to the manifests directory. Run the command:\n\n\
+             grep -i tls-min-version *\n\n\
+             If it is not set, this is a finding."
+        );
+    }
+
+    #[test]
+    fn normalize_block_keeps_relative_indentation() {
+        let raw = "\n  Set the following:\n    key = value\n  Restart.\n";
+        assert_eq!(normalize_block(raw), "Set the following:\n  key = value\nRestart.");
+        assert_eq!(normalize_block("First\n  second"), "First\nsecond");
+        assert_eq!(normalize_block(" \n\n "), "");
+    }
+
+    #[test]
+    fn rule_text_keeps_line_structure() {
+        let xml = XCCDF.replace(
+            "<check-content>Run the command.</check-content>",
+            "<check-content>Run the command:\n\n    grep -i tls *\n\n\
+             If unset, this is a finding.</check-content>",
+        );
+        let stig = parse(&xml);
+        assert_eq!(
+            stig.rules[0].check_text,
+            "Run the command:\n\n    grep -i tls *\n\nIf unset, this is a finding."
+        );
+    }
 
-        let category = infer_category(&stig.title).to_string();
-        entries.push(LibraryEntry { id, category, stig });
+    #[test]
+    fn json_from_older_parsers_is_stale() {
+        let stig = parse(XCCDF);
+        assert_eq!(stig.parser_version, Some(PARSER_VERSION));
+        assert!(!stig.is_stale());
+
+        let json = serde_json::to_string(&stig).unwrap();
+        let reread: StigData = serde_json::from_str(&json).unwrap();
+        assert!(!reread.is_stale());
+
+        let old = r#"{"title":"Kubernetes","description":"","version":"2","releaseInfo":"",
+            "rules":[]}"#;
+        let old: StigData = serde_json::from_str(old).unwrap();
+        assert!(old.is_stale());
+
+        // Written after stigId was fixed but before rule titles were
+        let v1 = StigData { parser_version: Some(1), ..Default::default() };
+        assert!(v1.is_stale());
+    }
+
+    #[test]
+    fn filename_to_id_drops_release_markers() {
+        assert_eq!(filename_to_id("U_MS_Windows_11_V2R3_STIG.zip"), "ms-windows-11");
+        assert_eq!(filename_to_id("U_RHEL_9_V2R2_STIG.zip"), "rhel-9");
+        assert_eq!(filename_to_id("U_Cisco_ASA_Y26M01_STIG.zip"), "cisco-asa");
+        assert_eq!(
+            filename_to_id("U_MS_Windows_11_V2R3_STIG_SCAP_1-3_Benchmark.zip"),
+            "ms-windows-11"
+        );
+        assert_eq!(filename_to_id("a/b/U_Kubernetes_STIG_V2R5_Manual-xccdf.xml"), "kubernetes");
+        assert_eq!(
+            filename_to_id("U_MS_SQL_Server_2016_Instance_V3R1_Manual_STIG.ZIP"),
+            "ms-sql-server-2016-instance"
+        );
+    }
+
+    #[test]
+    fn release_independent_id_normalizes_catalog_ids() {
+        assert_eq!(release_independent_id("cisco-asa-y26m01"), "cisco-asa");
+        assert_eq!(release_independent_id("rhel-9-v2r2"), "rhel-9");
+        assert_eq!(release_independent_id("kubernetes"), "kubernetes");
+        // Numbers that are not release markers are kept
+        assert_eq!(release_independent_id("oracle-linux-8-v1r9"), "oracle-linux-8");
+        assert_eq!(release_independent_id("juniper-srx-2"), "juniper-srx-2");
+        assert_eq!(release_independent_id("vmware-vsphere-7-0-esxi"), "vmware-vsphere-7-0-esxi");
+    }
+
+    #[test]
+    fn release_markers_need_both_numbers() {
+        assert!(is_release_marker("v2r3"));
+        assert!(is_release_marker("y25m04"));
+        assert!(!is_release_marker("v2"));
+        assert!(!is_release_marker("vr"));
+        assert!(!is_release_marker("y2025"));
+        assert!(!is_release_marker("server"));
     }
 
-    (entries, errors)
+    fn path(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn package_index_prefers_the_outermost_stig_zip() {
+        let nested = path(&[
+            "U_Cisco_Router.zip",
+            "U_Cisco_IOS_XE_Router_Y25M04_STIG.zip",
+            "U_Cisco_IOS_XE_Router_NDM_STIG_V3R4_Manual-xccdf.xml",
+        ]);
+        assert_eq!(package_index(&nested), 1);
+        let scap = path(&["U_RHEL_9_V2R2_STIG_SCAP_1-3_Benchmark.zip", "U_RHEL_9_Benchmark.xml"]);
+        assert_eq!(package_index(&scap), 0);
+    }
+
+    #[test]
+    fn package_index_falls_back_to_the_innermost_archive_or_the_entry() {
+        let other = path(&["Bundle.zip", "Extras.zip", "U_A_STIG_V1R1_Manual-xccdf.xml"]);
+        assert_eq!(package_index(&other), 1);
+        assert_eq!(package_index(&path(&["U_A_STIG_V1R1_Manual-xccdf.xml"])), 0);
+    }
+
+    #[test]
+    fn package_scan_lists_skipped_entries() {
+        let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default();
+        for (name, bytes) in [
+            ("U_Kubernetes_STIG_V2R5_Manual-xccdf.xml", XCCDF.as_bytes()),
+            ("U_Kubernetes_V2R5_Overview.pdf", b"%PDF"),
+        ] {
+            writer.start_file(name, options).unwrap();
+            std::io::Write::write_all(&mut writer, bytes).unwrap();
+        }
+        let zip = writer.finish().unwrap().into_inner();
+
+        let scan = extract_xccdf_from_zip(&zip, ZipLimits::default()).unwrap();
+        assert_eq!(scan.files.len(), 1);
+        assert_eq!(scan.skipped.len(), 1);
+        assert_eq!(scan.skipped[0].path, "U_Kubernetes_V2R5_Overview.pdf");
+        assert_eq!(scan.skipped[0].kind, EntryKind::Overview);
+        assert_eq!(scan.skipped[0].reason, "overview document");
+    }
 }
@@ -0,0 +1,323 @@
+//! XCCDF `<TestResult>` documents, as written by the SCAP Compliance Checker.
+//!
+//! SCC writes the benchmark it evaluated followed by one `<TestResult>` with
+//! the target's identity and a `<rule-result>` per selected rule.  Only the
+//! first `<TestResult>` is read; the benchmark's own rules come from the
+//! catalog instead.
+
+use anyhow::{bail, Result};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::io::BufRead;
+
+use super::{attr_value, short_id};
+
+/// A scan: who ran it against which target, and the result of every rule.
+#[derive(Debug, Clone, Default)]
+pub struct TestResult {
+    /// Benchmark id with the XCCDF 1.2 prefix stripped, as in `StigData`.
+    pub benchmark_id: String,
+    pub benchmark_title: String,
+    pub version: String,
+    pub target: String,
+    pub target_addresses: Vec<String>,
+    /// `<target-facts>` as `(name, value)`, e.g.
+    /// `("urn:scap:fact:asset:identifier:fqdn", "web01.example.mil")`.
+    pub facts: Vec<(String, String)>,
+    pub identity: String,
+    pub start_time: Option<String>,
+    pub end_time: Option<String>,
+    pub rule_results: Vec<RuleResult>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RuleResult {
+    /// Rule id with the XCCDF 1.2 prefix stripped, e.g. `SV-242376r960759_rule`.
+    pub rule_id: String,
+    /// `pass`, `fail`, `notapplicable`, `notchecked`, `error`, …
+    pub result: String,
+    pub time: Option<String>,
+}
+
+impl TestResult {
+    /// Value of the first target fact with the given name.
+    pub fn fact(&self, name: &str) -> Option<&str> {
+        self.facts
+            .iter()
+            .find(|(n, v)| n == name && !v.is_empty())
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Map an XCCDF `<result>` onto the frontend's status values.
+///
+/// Anything other than a definite pass, fail or not-applicable (`error`,
+/// `unknown`, `notchecked`, `informational`, …) needs a manual check.
+pub fn status_from_result(result: &str) -> Option<&'static str> {
+    match result {
+        "pass" | "fixed" => Some("not_a_finding"),
+        "fail" => Some("open"),
+        "notapplicable" => Some("not_applicable"),
+        _ => None,
+    }
+}
+
+/// Map a frontend status value onto an XCCDF `<result>`.
+pub fn result_from_status(status: &str) -> &'static str {
+    match status {
+        "not_a_finding" => "pass",
+        "open" => "fail",
+        "not_applicable" => "notapplicable",
+        _ => "notchecked",
+    }
+}
+
+/// Parse an XCCDF results document.
+pub fn parse_results_reader<R: BufRead>(xml: R) -> Result<TestResult> {
+    let mut reader = Reader::from_reader(super::encoding::utf8_xml(xml)?);
+    reader.config_mut().trim_text(true);
+
+    let mut result = TestResult::default();
+    let mut seen_test_result = false;
+    let mut in_test_result = false;
+    let mut current_rule: Option<RuleResult> = None;
+    // Local names of the currently open elements, outermost first
+    let mut path: Vec<String> = Vec::new();
+
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(ref e)) => {
+                let local = String::from_utf8_lossy(e.local_name().as_ref()).to_string();
+                match local.as_str() {
+                    "Benchmark" if path.iter().all(|p| p != "Benchmark") => {
+                        result.benchmark_id =
+                            short_id(attr_value(e.as_ref(), "id").unwrap_or_default());
+                    }
+                    "TestResult" if !seen_test_result => {
+                        seen_test_result = true;
+                        in_test_result = true;
+                        result.start_time = attr_value(e.as_ref(), "start-time");
+                        result.end_time = attr_value(e.as_ref(), "end-time");
+                        if result.version.is_empty() {
+                            result.version = attr_value(e.as_ref(), "version").unwrap_or_default();
+                        }
+                    }
+                    "benchmark" if in_test_result => apply_benchmark_ref(&mut result, e.as_ref()),
+                    "fact" if in_test_result => result.facts.push((
+                        attr_value(e.as_ref(), "name").unwrap_or_default(),
+                        String::new(),
+                    )),
+                    "rule-result" if in_test_result => {
+                        current_rule = Some(RuleResult {
+                            rule_id: short_id(attr_value(e.as_ref(), "idref").unwrap_or_default()),
+                            // XCCDF puts the result in a child element, but accept the attribute too
+                            result: attr_value(e.as_ref(), "result").unwrap_or_default(),
+                            time: attr_value(e.as_ref(), "time"),
+                        });
+                    }
+                    _ => {}
+                }
+                path.push(local);
+            }
+            Ok(Event::Empty(ref e))
+                if in_test_result && e.local_name().as_ref() == b"benchmark" =>
+            {
+                apply_benchmark_ref(&mut result, e.as_ref());
+            }
+            Ok(Event::End(ref e)) => {
+                match e.local_name().as_ref() {
+                    b"rule-result" => {
+                        if let Some(rule) = current_rule.take() {
+                            result.rule_results.push(rule);
+                        }
+                    }
+                    b"TestResult" => in_test_result = false,
+                    _ => {}
+                }
+                path.pop();
+            }
+            Ok(Event::Text(ref e)) => {
+                let text = e.unescape().unwrap_or_default().to_string();
+                if text.is_empty() {
+                    continue;
+                }
+                let (parent, tag) = match path.as_slice() {
+                    [.., parent, tag] => (parent.as_str(), tag.as_str()),
+                    _ => continue,
+                };
+                match (parent, tag) {
+                    ("Benchmark", "title") if result.benchmark_title.is_empty() => {
+                        result.benchmark_title = text
+                    }
+                    ("Benchmark", "version") if result.version.is_empty() => result.version = text,
+                    ("TestResult", "target") if in_test_result => result.target = text,
+                    ("TestResult", "target-address") if in_test_result => {
+                        result.target_addresses.push(text)
+                    }
+                    ("TestResult", "identity") if in_test_result => result.identity = text,
+                    ("target-facts", "fact") if in_test_result => {
+                        if let Some(fact) = result.facts.last_mut() {
+                            fact.1.push_str(&text);
+                        }
+                    }
+                    ("rule-result", "result") => {
+                        if let Some(rule) = current_rule.as_mut() {
+                            rule.result = text;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => bail!("XML parse error: {e}"),
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    if !seen_test_result {
+        bail!("No TestResult element found in XCCDF results");
+    }
+
+    Ok(result)
+}
+
+/// `<benchmark id="…" href="…"/>` names the evaluated benchmark; it is the
+/// only reference to it when the results are written without the Benchmark.
+fn apply_benchmark_ref(result: &mut TestResult, tag_bytes: &[u8]) {
+    if result.benchmark_id.is_empty() {
+        if let Some(id) = attr_value(tag_bytes, "id") {
+            result.benchmark_id = short_id(id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RESULTS: &str = r##"<?xml version="1.0" encoding="UTF-8"?>
+<cdf:Benchmark xmlns:cdf="http://checklists.nist.gov/xccdf/1.2" id="xccdf_mil.disa.stig_benchmark_Kubernetes_STIG">
+  <cdf:title>Kubernetes Security Technical Implementation Guide</cdf:title>
+  <cdf:version>2</cdf:version>
+  <cdf:Group id="xccdf_mil.disa.stig_group_V-242376"><cdf:title>SRG-APP-000014-CTR-000035</cdf:title>
+    <cdf:Rule id="xccdf_mil.disa.stig_rule_SV-242376r960759_rule"><cdf:title>t</cdf:title></cdf:Rule></cdf:Group>
+  <cdf:TestResult id="xccdf_mil.disa.stig_testresult_default" start-time="2026-10-01T10:00:00" end-time="2026-10-01T10:05:00">
+    <cdf:benchmark href="#other" id="xccdf_mil.disa.stig_benchmark_Other_STIG"/>
+    <cdf:title>SCAP Compliance Checker</cdf:title>
+    <cdf:identity authenticated="true" privileged="true">CORP\jdoe</cdf:identity>
+    <cdf:target>K8S-CP02</cdf:target>
+    <cdf:target-address>10.0.0.12</cdf:target-address>
+    <cdf:target-facts>
+      <cdf:fact name="urn:scap:fact:asset:identifier:host_name" type="string">k8s-cp02</cdf:fact>
+      <cdf:fact name="urn:scap:fact:asset:identifier:mac" type="string"></cdf:fact>
+    </cdf:target-facts>
+    <cdf:rule-result idref="xccdf_mil.disa.stig_rule_SV-242376r960759_rule" time="2026-10-01T10:01:00">
+      <cdf:result>fail</cdf:result><cdf:ident system="http://cyber.mil/cci">CCI-000068</cdf:ident>
+    </cdf:rule-result>
+    <cdf:rule-result idref="xccdf_mil.disa.stig_rule_SV-242377r111111_rule"><cdf:result>notchecked</cdf:result></cdf:rule-result>
+  </cdf:TestResult>
+  <cdf:TestResult id="xccdf_mil.disa.stig_testresult_second">
+    <cdf:target>IGNORED</cdf:target>
+    <cdf:rule-result idref="xccdf_mil.disa.stig_rule_SV-1r1_rule"><cdf:result>pass</cdf:result></cdf:rule-result>
+  </cdf:TestResult>
+</cdf:Benchmark>"##;
+
+    fn parse(xml: &str) -> Result<TestResult> {
+        parse_results_reader(xml.as_bytes())
+    }
+
+    #[test]
+    fn reads_benchmark_and_target() {
+        let results = parse(RESULTS).unwrap();
+        // The enclosing Benchmark wins over the TestResult's reference
+        assert_eq!(results.benchmark_id, "Kubernetes_STIG");
+        assert_eq!(
+            results.benchmark_title,
+            "Kubernetes Security Technical Implementation Guide"
+        );
+        assert_eq!(results.version, "2");
+        assert_eq!(results.target, "K8S-CP02");
+        assert_eq!(results.target_addresses, ["10.0.0.12"]);
+        assert_eq!(results.identity, r"CORP\jdoe");
+        assert_eq!(results.start_time.as_deref(), Some("2026-10-01T10:00:00"));
+        assert_eq!(results.end_time.as_deref(), Some("2026-10-01T10:05:00"));
+    }
+
+    #[test]
+    fn reads_facts_skipping_empty_values() {
+        let results = parse(RESULTS).unwrap();
+        let fact = |name: &str| results.fact(&format!("urn:scap:fact:asset:identifier:{name}"));
+        assert_eq!(fact("host_name"), Some("k8s-cp02"));
+        assert_eq!(fact("mac"), None);
+        assert_eq!(fact("fqdn"), None);
+    }
+
+    #[test]
+    fn reads_rule_results_of_the_first_test_result_only() {
+        let results = parse(RESULTS).unwrap();
+        let rules: Vec<(&str, &str)> = results
+            .rule_results
+            .iter()
+            .map(|r| (r.rule_id.as_str(), r.result.as_str()))
+            .collect();
+        assert_eq!(
+            rules,
+            [
+                ("SV-242376r960759_rule", "fail"),
+                ("SV-242377r111111_rule", "notchecked")
+            ]
+        );
+        assert_eq!(
+            results.rule_results[0].time.as_deref(),
+            Some("2026-10-01T10:01:00")
+        );
+        assert_eq!(results.rule_results[1].time, None);
+    }
+
+    #[test]
+    fn benchmark_reference_names_a_bare_test_result() {
+        let xml = r#"<TestResult xmlns="http://checklists.nist.gov/xccdf/1.2" version="3">
+  <benchmark id="xccdf_mil.disa.stig_benchmark_Kubernetes_STIG"/>
+  <rule-result idref="xccdf_mil.disa.stig_rule_SV-242376r960759_rule" result="pass"/>
+</TestResult>"#;
+        let results = parse(xml).unwrap();
+        assert_eq!(results.benchmark_id, "Kubernetes_STIG");
+        assert_eq!(results.version, "3");
+    }
+
+    #[test]
+    fn rejects_a_document_without_test_result() {
+        let xml = r#"<Benchmark xmlns="http://checklists.nist.gov/xccdf/1.2" id="b"><title>t</title></Benchmark>"#;
+        let err = parse(xml).unwrap_err();
+        assert!(err.to_string().contains("No TestResult"), "{err}");
+    }
+
+    #[test]
+    fn status_from_result_maps_definite_results() {
+        assert_eq!(status_from_result("pass"), Some("not_a_finding"));
+        assert_eq!(status_from_result("fixed"), Some("not_a_finding"));
+        assert_eq!(status_from_result("fail"), Some("open"));
+        assert_eq!(status_from_result("notapplicable"), Some("not_applicable"));
+        for result in [
+            "error",
+            "unknown",
+            "notchecked",
+            "notselected",
+            "informational",
+        ] {
+            assert_eq!(status_from_result(result), None, "{result}");
+        }
+    }
+
+    #[test]
+    fn result_from_status_round_trips() {
+        for status in ["not_a_finding", "open", "not_applicable"] {
+            assert_eq!(status_from_result(result_from_status(status)), Some(status));
+        }
+        assert_eq!(result_from_status("not_reviewed"), "notchecked");
+        assert_eq!(result_from_status(""), "notchecked");
+    }
+}
@@ -0,0 +1,202 @@
+//! Content problems found while parsing an XCCDF benchmark.
+//!
+//! None of these stop a benchmark from importing, but each is something an
+//! assessor would trip over: a rule with nothing to check, a severity DISA
+//! never uses, two rules under one id.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use super::Rule;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WarningKind {
+    MissingCheckText,
+    MissingFixText,
+    DuplicateRuleId,
+    DuplicateGroupId,
+    NoCci,
+    /// Mapped to CAT II by `map_severity`.
+    UnknownSeverity,
+    /// The benchmark has no `<title>`; it was imported as "Unknown STIG".
+    UnknownTitle,
+}
+
+impl WarningKind {
+    /// The serialized name, e.g. `missing_fix_text`.
+    fn label(self) -> String {
+        match serde_json::to_value(self) {
+            Ok(serde_json::Value::String(label)) => label,
+            _ => format!("{self:?}"),
+        }
+    }
+}
+
+/// One problem, and the Rule or Group it concerns.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParseWarning {
+    pub kind: WarningKind,
+    /// Rule id, or Group id for `duplicate_group_id`; absent for the benchmark.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub message: String,
+}
+
+/// Counts by kind for log lines, e.g. `2 missing_fix_text, 14 no_cci`.
+pub fn summarize(warnings: &[ParseWarning]) -> String {
+    let mut kinds: Vec<WarningKind> = warnings.iter().map(|w| w.kind).collect();
+    kinds.sort();
+    let mut parts: Vec<String> = Vec::new();
+    for chunk in kinds.chunk_by(|a, b| a == b) {
+        parts.push(format!("{} {}", chunk.len(), chunk[0].label()));
+    }
+    parts.join(", ")
+}
+
+/// Collects warnings as `parse_xccdf_reader` walks the benchmark.
+#[derive(Default)]
+pub(crate) struct Diagnostics {
+    warnings: Vec<ParseWarning>,
+    rule_ids: HashSet<String>,
+    group_ids: HashSet<String>,
+}
+
+impl Diagnostics {
+    fn push(&mut self, kind: WarningKind, id: Option<&str>, message: String) {
+        self.warnings.push(ParseWarning {
+            kind,
+            id: id.map(str::to_string),
+            message,
+        });
+    }
+
+    pub fn group(&mut self, group_id: &str) {
+        if !self.group_ids.insert(group_id.to_string()) {
+            self.push(
+                WarningKind::DuplicateGroupId,
+                Some(group_id),
+                format!("Group {group_id} appears more than once"),
+            );
+        }
+    }
+
+    /// Check the raw `severity` attribute before `map_severity` folds it.
+    pub fn severity(&mut self, rule_id: &str, raw: &str) {
+        if !matches!(raw.to_lowercase().as_str(), "high" | "medium" | "low") {
+            let message = if raw.is_empty() {
+                "Rule has no severity; treated as CAT II".to_string()
+            } else {
+                format!("Unknown severity '{raw}'; treated as CAT II")
+            };
+            self.push(WarningKind::UnknownSeverity, Some(rule_id), message);
+        }
+    }
+
+    /// Check a rule once all of its content has been read.
+    pub fn rule(&mut self, rule: &Rule) {
+        let id = Some(rule.id.as_str());
+        if !self.rule_ids.insert(rule.id.clone()) {
+            self.push(
+                WarningKind::DuplicateRuleId,
+                id,
+                format!("Rule {} appears more than once", rule.id),
+            );
+        }
+        // SCAP rules point at an OVAL definition instead of carrying check text
+        let automated = rule
+            .check_content_ref
+            .as_ref()
+            .is_some_and(|r| r.name != "M");
+        if rule.check_text.is_empty() && !automated {
+            self.push(
+                WarningKind::MissingCheckText,
+                id,
+                "Rule has no check text".to_string(),
+            );
+        }
+        if rule.fix_text.is_empty() {
+            self.push(
+                WarningKind::MissingFixText,
+                id,
+                "Rule has no fix text".to_string(),
+            );
+        }
+        if rule.cci_ids.is_empty() {
+            self.push(WarningKind::NoCci, id, "Rule references no CCI".to_string());
+        }
+    }
+
+    pub fn unknown_title(&mut self) {
+        self.push(
+            WarningKind::UnknownTitle,
+            None,
+            "Benchmark has no title; imported as \"Unknown STIG\"".to_string(),
+        );
+    }
+
+    pub fn finish(self) -> Vec<ParseWarning> {
+        self.warnings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn warning(kind: WarningKind) -> ParseWarning {
+        ParseWarning {
+            kind,
+            id: None,
+            message: String::new(),
+        }
+    }
+
+    #[test]
+    fn label_is_the_serialized_name() {
+        assert_eq!(WarningKind::MissingFixText.label(), "missing_fix_text");
+        assert_eq!(WarningKind::NoCci.label(), "no_cci");
+        let json = serde_json::to_string(&warning(WarningKind::DuplicateGroupId)).unwrap();
+        assert_eq!(json, r#"{"kind":"duplicate_group_id","message":""}"#);
+    }
+
+    #[test]
+    fn summarize_counts_by_kind() {
+        let warnings = [
+            warning(WarningKind::NoCci),
+            warning(WarningKind::MissingFixText),
+            warning(WarningKind::NoCci),
+        ];
+        assert_eq!(summarize(&warnings), "1 missing_fix_text, 2 no_cci");
+        assert_eq!(summarize(&[]), "");
+    }
+
+    #[test]
+    fn diagnostics_flag_rule_problems() {
+        let mut diagnostics = Diagnostics::default();
+        let rule = Rule {
+            id: "SV-1r1_rule".to_string(),
+            ..Default::default()
+        };
+        diagnostics.rule(&rule);
+        diagnostics.rule(&rule);
+        diagnostics.severity(&rule.id, "critical");
+        diagnostics.group("V-1");
+        diagnostics.group("V-1");
+        let mut kinds: Vec<WarningKind> = diagnostics.finish().iter().map(|w| w.kind).collect();
+        kinds.sort();
+        kinds.dedup();
+        assert_eq!(
+            kinds,
+            [
+                WarningKind::MissingCheckText,
+                WarningKind::MissingFixText,
+                WarningKind::DuplicateRuleId,
+                WarningKind::DuplicateGroupId,
+                WarningKind::NoCci,
+                WarningKind::UnknownSeverity,
+            ]
+        );
+    }
+}
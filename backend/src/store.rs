@@ -0,0 +1,432 @@
+use anyhow::{Context, Result};
+use sqlx::PgPool;
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{PoisonError, RwLock},
+};
+
+use crate::config::Config;
+use crate::db::{
+    get_catalog_entry, list_catalog, list_checklists, merge_catalog_entry, set_parser_version,
+    set_scap_benchmark, set_srg_ids, upsert_catalog, upsert_checklist, CatalogEntry, CatalogFilter,
+    ChecklistEntry,
+};
+use crate::parser::{
+    ckl::Checklist, extract_all_from_library, extract_xccdf_from_zip, package_ids,
+    release_independent_id, StigData,
+};
+use crate::taxonomy::{CategorySource, Classification, Taxonomy};
+
+/// Persist one benchmark parsed from a STIG package.
+///
+/// Manual benchmarks are written to `<stigs_dir>/<id>.json` and upserted into
+/// the catalog.  A SCAP benchmark only records its version, release and
+/// warnings on the existing entry; its rules become the entry's content only
+/// when no manual STIG has been imported under that id yet.
+///
+/// Returns `true` when the benchmark's rules were written as the entry's content.
+pub async fn save_benchmark(
+    pool: &PgPool,
+    stigs_dir: &Path,
+    id: &str,
+    classification: &Classification,
+    stig: &StigData,
+    scap: bool,
+) -> Result<bool> {
+    if scap && set_scap_benchmark(pool, id, stig).await? {
+        return Ok(false);
+    }
+
+    let json_path = stigs_dir.join(format!("{id}.json"));
+    let json_str = serde_json::to_string(stig).context("Serialisation failed")?;
+    tokio::fs::write(&json_path, &json_str)
+        .await
+        .with_context(|| format!("Failed to write {}", json_path.display()))?;
+
+    let entry = CatalogEntry::from_stig(id, classification, stig, &json_path);
+    upsert_catalog(pool, &entry)
+        .await
+        .context("Database upsert failed")?;
+
+    if scap {
+        set_scap_benchmark(pool, id, stig).await?;
+    }
+    Ok(true)
+}
+
+/// Retire the `base_id` entry of a package that now imports as several
+/// benchmarks (see `parser::benchmark_ids`), once they have been saved.
+///
+/// The old entry held one of the package's benchmarks.  It is folded into the
+/// saved entry with the same benchmark id (else the same title, else the
+/// first), checklists linked to it are repointed, and its JSON is removed.
+///
+/// Returns the ID it was folded into, or `None` when there was nothing to fold.
+pub async fn fold_split_package(
+    pool: &PgPool,
+    stigs_dir: &Path,
+    base_id: &str,
+    saved: &[(&str, &StigData)],
+) -> Result<Option<String>> {
+    if saved.is_empty() || saved.iter().any(|(id, _)| *id == base_id) {
+        return Ok(None);
+    }
+    let Some(old) = get_catalog_entry(pool, base_id).await? else {
+        return Ok(None);
+    };
+
+    let (new_id, _) = saved
+        .iter()
+        .find(|(_, stig)| !old.benchmark_id.is_empty() && stig.benchmark_id == old.benchmark_id)
+        .or_else(|| saved.iter().find(|(_, stig)| stig.title == old.title))
+        .unwrap_or(&saved[0]);
+
+    let old_path = stigs_dir.join(format!("{base_id}.json"));
+    if let Err(e) = tokio::fs::remove_file(&old_path).await {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            return Err(e).with_context(|| format!("Failed to remove {}", old_path.display()));
+        }
+    }
+    repoint_checklists(pool, base_id, new_id).await?;
+    let json_path = stigs_dir.join(format!("{new_id}.json"));
+    merge_catalog_entry(pool, base_id, new_id, false, &json_path.to_string_lossy()).await?;
+    tracing::info!("Catalog entry '{base_id}' merged into '{new_id}' now that its package splits");
+    Ok(Some(new_id.to_string()))
+}
+
+/// Persist a checklist to `<checklists_dir>/<id>.json` and upsert its row.
+pub async fn save_checklist(
+    pool: &PgPool,
+    checklists_dir: &Path,
+    id: &str,
+    name: &str,
+    source_format: &str,
+    checklist: &Checklist,
+) -> Result<ChecklistEntry> {
+    let json_path = checklists_dir.join(format!("{id}.json"));
+    let json_str = serde_json::to_string(checklist).context("Serialisation failed")?;
+    tokio::fs::write(&json_path, &json_str)
+        .await
+        .with_context(|| format!("Failed to write {}", json_path.display()))?;
+
+    let entry = ChecklistEntry::from_checklist(id, name, source_format, checklist, &json_path);
+    upsert_checklist(pool, &entry)
+        .await
+        .context("Database upsert failed")?;
+    Ok(entry)
+}
+
+/// Rename catalog entries whose IDs still carry a release marker
+/// (`cisco-asa-y26m01` → `cisco-asa`), run once at startup.
+///
+/// When several releases of a product were imported under different IDs,
+/// the one with the latest benchmark date wins; the others are dropped along
+/// with their JSON.  Checklists linked to a renamed entry are repointed.
+///
+/// Returns the number of entries renamed or dropped.
+pub async fn migrate_release_ids(pool: &PgPool, data_dir: &Path) -> Result<usize> {
+    let stigs_dir = data_dir.join("stigs");
+    let mut entries: HashMap<String, CatalogEntry> = list_catalog(pool, &CatalogFilter::default())
+        .await?
+        .into_iter()
+        .map(|e| (e.id.clone(), e))
+        .collect();
+    let mut stale: Vec<String> = entries
+        .keys()
+        .filter(|id| release_independent_id(id) != **id)
+        .cloned()
+        .collect();
+    stale.sort();
+
+    for old_id in &stale {
+        let new_id = release_independent_id(old_id);
+        let Some(mut old) = entries.remove(old_id) else { continue };
+        let replace = entries.get(&new_id).is_none_or(|current| {
+            (old.benchmark_date, old.last_updated) > (current.benchmark_date, current.last_updated)
+        });
+
+        let old_path = stigs_dir.join(format!("{old_id}.json"));
+        let new_path = stigs_dir.join(format!("{new_id}.json"));
+        let moved = if replace {
+            tokio::fs::rename(&old_path, &new_path).await
+        } else {
+            tokio::fs::remove_file(&old_path).await
+        };
+        // Files shipped in the repository are already renamed
+        if let Err(e) = moved {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                return Err(e).with_context(|| format!("Failed to move {}", old_path.display()));
+            }
+        }
+
+        repoint_checklists(pool, old_id, &new_id).await?;
+        let json_path = new_path.to_string_lossy().into_owned();
+        merge_catalog_entry(pool, old_id, &new_id, replace, &json_path).await?;
+        tracing::info!(
+            "Catalog entry '{old_id}' {} '{new_id}'",
+            if replace { "renamed to" } else { "merged into" }
+        );
+
+        if replace {
+            old.id = new_id.clone();
+            old.json_path = json_path;
+            entries.insert(new_id, old);
+        }
+    }
+    Ok(stale.len())
+}
+
+/// Find STIG JSON files written by an older parser, run once at startup.
+///
+/// Every `<data_dir>/stigs/*.json` is read and the catalog's `parser_version`
+/// and `srg_ids` brought in line with the file, so `GET /api/catalog?stale=true`
+/// lists what needs re-importing and entries saved before `srg_ids` was
+/// recorded are found by `GET /api/srg/:srg_id`.  Files are not rewritten here; see
+/// [`reimport_stale_benchmarks`].
+///
+/// Returns the IDs of the stale files, sorted.
+pub async fn find_stale_benchmarks(pool: &PgPool, data_dir: &Path) -> Result<Vec<String>> {
+    let catalog: HashMap<String, (i32, Vec<String>)> =
+        list_catalog(pool, &CatalogFilter::default())
+            .await?
+            .into_iter()
+            .map(|e| (e.id, (e.parser_version, e.srg_ids)))
+            .collect();
+
+    let stigs_dir = data_dir.join("stigs");
+    let mut dir = tokio::fs::read_dir(&stigs_dir)
+        .await
+        .with_context(|| format!("Failed to read {}", stigs_dir.display()))?;
+    let mut stale = Vec::new();
+    while let Some(file) = dir.next_entry().await? {
+        let path = file.path();
+        let Some(id) = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(|n| n.strip_suffix(".json"))
+            .map(str::to_string)
+        else {
+            continue;
+        };
+        let contents = tokio::fs::read_to_string(&path)
+            .await
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let stig: StigData = match serde_json::from_str(&contents) {
+            Ok(stig) => stig,
+            Err(e) => {
+                tracing::warn!("Skipping {}: {e}", path.display());
+                continue;
+            }
+        };
+
+        if let Some((catalog_version, catalog_srg_ids)) = catalog.get(&id) {
+            let parser_version = stig.parser_version.unwrap_or(0) as i32;
+            if *catalog_version != parser_version {
+                set_parser_version(pool, &id, parser_version).await?;
+            }
+            let srg_ids = stig.srg_ids();
+            if *catalog_srg_ids != srg_ids {
+                set_srg_ids(pool, &id, &srg_ids).await?;
+            }
+        }
+        if stig.is_stale() {
+            stale.push(id);
+        }
+    }
+    stale.sort();
+    Ok(stale)
+}
+
+/// Benchmarks re-imported at startup, and those still stale.
+pub struct Reimport {
+    pub reimported: Vec<String>,
+    pub remaining: Vec<String>,
+}
+
+/// Path a package imported as `id` is kept at.  SCAP benchmark packages
+/// share their STIG's ID, so they are kept alongside it as `<id>.scap.zip`.
+pub fn package_path(data_dir: &Path, id: &str, scap: bool) -> PathBuf {
+    let suffix = if scap { ".scap.zip" } else { ".zip" };
+    data_dir.join("packages").join(format!("{id}{suffix}"))
+}
+
+/// Path the last library bundle uploaded is kept at.
+pub fn library_path(data_dir: &Path) -> PathBuf {
+    data_dir.join("library.zip")
+}
+
+/// Keep a package imported as `id` (see [`package_path`]) so that its
+/// benchmarks can be parsed again by a later release.
+pub async fn keep_package(data_dir: &Path, id: &str, scap: bool, zip_bytes: &[u8]) -> Result<()> {
+    let path = package_path(data_dir, id, scap);
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir).await?;
+    }
+    tokio::fs::write(&path, zip_bytes)
+        .await
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Re-import STIG JSON written by an older parser, run once at startup.
+///
+/// Stale benchmarks (see [`find_stale_benchmarks`]) are parsed again from the
+/// package they were imported from: the uploaded or synced package kept by
+/// [`keep_package`], else the last library bundle.  Categories pinned by the
+/// manifest or an upload are kept.  Benchmarks imported before packages were
+/// kept stay stale until their source is synced or uploaded again.
+pub async fn reimport_stale_benchmarks(
+    pool: &PgPool,
+    config: &Config,
+    taxonomy: &RwLock<Taxonomy>,
+) -> Result<Reimport> {
+    let mut stale = find_stale_benchmarks(pool, &config.data_dir).await?;
+    let stigs_dir = config.data_dir.join("stigs");
+    let mut reimported = Vec::new();
+
+    let packages_dir = config.data_dir.join("packages");
+    let mut packages = Vec::new();
+    match tokio::fs::read_dir(&packages_dir).await {
+        Ok(mut dir) => {
+            while let Some(file) = dir.next_entry().await? {
+                packages.push(file.path());
+            }
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => {
+            return Err(e).with_context(|| format!("Failed to read {}", packages_dir.display()))
+        }
+    }
+    // Manual packages before SCAP ones, so a STIG's rules are in place first
+    packages.sort_by_key(|path| path.to_string_lossy().ends_with(".scap.zip"));
+
+    for path in packages {
+        let Some(base_id) = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(|n| n.strip_suffix(".zip"))
+            .map(|n| n.trim_end_matches(".scap").to_string())
+        else {
+            continue;
+        };
+        let prefix = format!("{base_id}-");
+        if !stale.iter().any(|id| *id == base_id || id.starts_with(&prefix)) {
+            continue;
+        }
+
+        let zip_bytes = tokio::fs::read(&path)
+            .await
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let scan = match extract_xccdf_from_zip(&zip_bytes, config.zip_limits) {
+            Ok(scan) => scan,
+            Err(e) => {
+                tracing::warn!("Cannot re-import {}: {e:#}", path.display());
+                continue;
+            }
+        };
+        let mut parsed = Vec::new();
+        for (id, file) in package_ids(&base_id, scan.files) {
+            match file.stig {
+                Ok(stig) => parsed.push((id, file.scap, stig)),
+                Err(e) => tracing::warn!("Cannot re-import {}: {e:#}", file.name),
+            }
+        }
+        if !parsed.iter().any(|(id, _, _)| stale.contains(id)) {
+            continue;
+        }
+
+        for (id, scap, stig) in &parsed {
+            if reimport_benchmark(pool, &stigs_dir, taxonomy, id, stig, *scap).await? {
+                reimported.push(id.clone());
+            }
+        }
+        let saved: Vec<(&str, &StigData)> =
+            parsed.iter().map(|(id, _, stig)| (id.as_str(), stig)).collect();
+        fold_split_package(pool, &stigs_dir, &base_id, &saved).await?;
+        stale.retain(|id| !reimported.contains(id));
+    }
+
+    let library = library_path(&config.data_dir);
+    if !stale.is_empty() && tokio::fs::try_exists(&library).await? {
+        // Parsing is CPU-bound — run on the blocking thread pool
+        let limits = config.zip_limits;
+        let scan = tokio::task::spawn_blocking(move || {
+            std::fs::File::open(&library)
+                .map(|f| extract_all_from_library(std::io::BufReader::new(f), limits))
+        })
+        .await?
+        .context("Failed to open the library bundle")?;
+
+        // Whole packages are re-imported so that split packages fold as on upload
+        let mut stale_packages: Vec<&str> = scan
+            .entries
+            .iter()
+            .filter(|e| stale.contains(&e.id))
+            .map(|e| e.package_id.as_str())
+            .collect();
+        stale_packages.dedup();
+        for package_id in stale_packages {
+            let entries: Vec<_> =
+                scan.entries.iter().filter(|e| e.package_id == package_id).collect();
+            for entry in &entries {
+                let id = &entry.id;
+                if reimport_benchmark(pool, &stigs_dir, taxonomy, id, &entry.stig, entry.scap)
+                    .await?
+                {
+                    reimported.push(id.clone());
+                }
+            }
+            let saved: Vec<(&str, &StigData)> =
+                entries.iter().map(|e| (e.id.as_str(), &e.stig)).collect();
+            fold_split_package(pool, &stigs_dir, package_id, &saved).await?;
+        }
+        stale.retain(|id| !reimported.contains(id));
+    }
+
+    Ok(Reimport { reimported, remaining: stale })
+}
+
+/// Save one re-parsed benchmark, keeping a pinned catalog category.
+///
+/// Returns `true` when its rules were written (see [`save_benchmark`]).
+async fn reimport_benchmark(
+    pool: &PgPool,
+    stigs_dir: &Path,
+    taxonomy: &RwLock<Taxonomy>,
+    id: &str,
+    stig: &StigData,
+    scap: bool,
+) -> Result<bool> {
+    let mut classification =
+        taxonomy.read().unwrap_or_else(PoisonError::into_inner).classify(&stig.title);
+    if let Some(entry) = get_catalog_entry(pool, id).await? {
+        if entry.category_source != CategorySource::Taxonomy {
+            classification = classification.pin(entry.category, entry.category_source);
+        }
+    }
+    save_benchmark(pool, stigs_dir, id, &classification, stig, scap).await
+}
+
+/// Rewrite the `catalogId` of stored checklists linked to `old_id`.
+async fn repoint_checklists(pool: &PgPool, old_id: &str, new_id: &str) -> Result<()> {
+    for entry in list_checklists(pool).await? {
+        if !entry.stig_ids.iter().any(|id| id == old_id) {
+            continue;
+        }
+        let contents = tokio::fs::read_to_string(&entry.json_path)
+            .await
+            .with_context(|| format!("Failed to read {}", entry.json_path))?;
+        let mut checklist: Checklist = serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to deserialise {}", entry.json_path))?;
+        for stig in &mut checklist.stigs {
+            if stig.catalog_id.as_deref() == Some(old_id) {
+                stig.catalog_id = Some(new_id.to_string());
+            }
+        }
+        let json_str = serde_json::to_string(&checklist).context("Serialisation failed")?;
+        tokio::fs::write(&entry.json_path, &json_str)
+            .await
+            .with_context(|| format!("Failed to write {}", entry.json_path))?;
+    }
+    Ok(())
+}
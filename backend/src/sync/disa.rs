@@ -1,13 +1,13 @@
 use anyhow::{Context, Result};
-use chrono::Utc;
 use sqlx::PgPool;
-use std::{path::Path, sync::Arc};
+use std::sync::{Arc, PoisonError, RwLock};
 use tracing::{error, info, warn};
 
 use crate::{
     config::{Config, StigSource},
-    db::{upsert_catalog, CatalogEntry},
-    parser::{extract_xccdf_from_zip, parse_xccdf},
+    parser::{extract_xccdf_from_zip, package_ids, warnings::summarize, StigData},
+    store::{fold_split_package, keep_package, save_benchmark},
+    taxonomy::{CategorySource, Taxonomy},
 };
 
 /// Download, parse, and index one STIG from DISA.
@@ -15,7 +15,8 @@ async fn sync_one(
     source: &StigSource,
     client: &reqwest::Client,
     pool: &PgPool,
-    data_dir: &Path,
+    config: &Config,
+    taxonomy: &RwLock<Taxonomy>,
 ) -> Result<()> {
     info!("Syncing STIG '{}' from {}", source.id, source.url);
 
@@ -32,42 +33,60 @@ async fn sync_one(
 
     let zip_bytes = resp.bytes().await.context("Failed to read response body")?;
 
-    // 2. Extract XCCDF XML from ZIP (handles double-zipped STIGs)
-    let xccdf =
-        extract_xccdf_from_zip(&zip_bytes).context("Failed to extract XCCDF from ZIP")?;
-
-    // 3. Parse XCCDF → StigData
-    let stig = parse_xccdf(&xccdf).context("Failed to parse XCCDF")?;
+    // 2. Extract every XCCDF XML from the ZIP (handles double-zipped STIGs)
+    let scan = extract_xccdf_from_zip(&zip_bytes, config.zip_limits)
+        .context("Failed to extract XCCDF from ZIP")?;
+    for skipped in &scan.skipped {
+        info!("'{}': skipped {}: {}", source.id, skipped.path, skipped.reason);
+    }
 
-    // 4. Write JSON file
-    let stigs_dir = data_dir.join("stigs");
+    let stigs_dir = config.data_dir.join("stigs");
     tokio::fs::create_dir_all(&stigs_dir).await?;
-    let json_path = stigs_dir.join(format!("{}.json", source.id));
-    let json_str = serde_json::to_string(&stig)?;
-    tokio::fs::write(&json_path, json_str).await?;
 
-    // 5. Upsert catalog row in Postgres
-    let entry = CatalogEntry {
-        id: source.id.clone(),
+    // Multi-benchmark packages are indexed as one catalog entry per benchmark
+    let mut saved = Vec::new();
+    let mut scap_only = true;
+    for (id, file) in package_ids(&source.id, scan.files) {
+        // 3. Parse XCCDF → StigData
+        let mut stig = file
+            .stig
+            .with_context(|| format!("Failed to parse XCCDF {}", file.name))?;
         // Prefer title parsed from XCCDF; fall back to the manifest title
-        title: if stig.title.is_empty() { source.title.clone() } else { stig.title.clone() },
-        category: source.category.clone(),
-        version: stig.version.clone(),
-        release_info: stig.release_info.clone(),
-        rule_count: stig.rules.len() as i32,
-        json_path: json_path.to_string_lossy().into_owned(),
-        last_updated: Utc::now(),
-    };
-    upsert_catalog(pool, &entry)
-        .await
-        .context("Failed to upsert catalog entry")?;
+        if stig.title.is_empty() {
+            stig.title = source.title.clone();
+        }
+
+        // 4. Write JSON file and upsert catalog row in Postgres; the manifest
+        // pins the category, the taxonomy adds the tags
+        let classification = taxonomy
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .classify(&stig.title)
+            .pin(source.category.clone(), CategorySource::Manifest);
+        save_benchmark(pool, &stigs_dir, &id, &classification, &stig, file.scap)
+            .await
+            .context("Failed to store STIG")?;
+
+        info!(
+            "Synced '{}': {} rules{}",
+            id,
+            stig.rules.len(),
+            if file.scap { " (SCAP benchmark)" } else { "" }
+        );
+        if !stig.warnings.is_empty() {
+            warn!("'{}' parsed with warnings: {}", id, summarize(&stig.warnings));
+        }
+        scap_only &= file.scap;
+        saved.push((id, stig));
+    }
+
+    // A source that used to import as one benchmark under its own ID now splits
+    let saved: Vec<(&str, &StigData)> =
+        saved.iter().map(|(id, stig)| (id.as_str(), stig)).collect();
+    fold_split_package(pool, &stigs_dir, &source.id, &saved).await?;
 
-    info!(
-        "Synced '{}': {} rules → {}",
-        source.id,
-        stig.rules.len(),
-        json_path.display()
-    );
+    // Kept so that a later release can parse it again without a download
+    keep_package(&config.data_dir, &source.id, scap_only, &zip_bytes).await?;
     Ok(())
 }
 
@@ -76,6 +95,7 @@ pub async fn run_sync(
     config: &Arc<Config>,
     sources: &Arc<Vec<StigSource>>,
     pool: &PgPool,
+    taxonomy: &RwLock<Taxonomy>,
 ) -> Result<()> {
     let client = reqwest::Client::builder()
         .user_agent("stig-viewer-backend/0.1")
@@ -84,7 +104,7 @@ pub async fn run_sync(
 
     let mut errors = 0usize;
     for source in sources.as_ref() {
-        if let Err(e) = sync_one(source, &client, pool, &config.data_dir).await {
+        if let Err(e) = sync_one(source, &client, pool, config, taxonomy).await {
             error!("Failed to sync '{}': {e:#}", source.id);
             errors += 1;
         }
@@ -0,0 +1,193 @@
+//! Catalog categories and tags, assigned from the rules in `taxonomy.toml`.
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+/// Top-level structure of taxonomy.toml.
+#[derive(Debug, Deserialize)]
+struct TaxonomyFile {
+    default_category: String,
+    #[serde(default)]
+    rules: Vec<RuleFile>,
+}
+
+/// One `[[rules]]` entry of taxonomy.toml.
+#[derive(Debug, Deserialize)]
+struct RuleFile {
+    category: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    keywords: Vec<String>,
+    pattern: Option<String>,
+}
+
+/// Ordered classification rules, compiled from taxonomy.toml.
+#[derive(Debug)]
+pub struct Taxonomy {
+    default_category: String,
+    rules: Vec<TaxonomyRule>,
+}
+
+#[derive(Debug)]
+struct TaxonomyRule {
+    category: Option<String>,
+    tags: Vec<String>,
+    /// Lowercased; matched as whole words.
+    keywords: Vec<String>,
+    pattern: Option<Regex>,
+}
+
+/// Category and tags the taxonomy assigns to one STIG.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Classification {
+    pub category: String,
+    /// Every matching rule's category and tags, in rule order.
+    pub tags: Vec<String>,
+    pub source: CategorySource,
+}
+
+/// Where a catalog entry's category came from.  Only taxonomy categories are
+/// replaced when the taxonomy is reloaded; the others are pinned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+pub enum CategorySource {
+    Taxonomy,
+    /// The `category` of the source in stig-sources.toml.
+    Manifest,
+    /// The `category` field of an upload.
+    Upload,
+}
+
+impl Classification {
+    /// Replace the category with one given by the manifest or an upload,
+    /// keeping the taxonomy's tags.
+    pub fn pin(mut self, category: String, source: CategorySource) -> Self {
+        self.category = category;
+        self.source = source;
+        self
+    }
+}
+
+impl Taxonomy {
+    pub fn from_toml(raw: &str) -> Result<Self> {
+        let parsed: TaxonomyFile = toml::from_str(raw).context("Failed to parse taxonomy.toml")?;
+        let rules = parsed
+            .rules
+            .into_iter()
+            .enumerate()
+            .map(|(n, rule)| {
+                let pattern = rule
+                    .pattern
+                    .as_deref()
+                    .map(Regex::new)
+                    .transpose()
+                    .with_context(|| format!("Invalid pattern in taxonomy rule {}", n + 1))?;
+                Ok(TaxonomyRule {
+                    category: rule.category,
+                    tags: rule.tags,
+                    keywords: rule.keywords.iter().map(|k| k.to_lowercase()).collect(),
+                    pattern,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self { default_category: parsed.default_category, rules })
+    }
+
+    /// Classify a STIG by its benchmark title.
+    pub fn classify(&self, title: &str) -> Classification {
+        let lower = title.to_lowercase();
+        let mut category: Option<&str> = None;
+        let mut tags: Vec<String> = Vec::new();
+
+        for rule in self.rules.iter().filter(|r| r.matches(title, &lower)) {
+            if let Some(rule_category) = &rule.category {
+                category.get_or_insert(rule_category);
+            }
+            for tag in rule.category.iter().chain(&rule.tags) {
+                if !tags.contains(tag) {
+                    tags.push(tag.clone());
+                }
+            }
+        }
+
+        Classification {
+            category: category.unwrap_or(&self.default_category).to_string(),
+            tags,
+            source: CategorySource::Taxonomy,
+        }
+    }
+}
+
+impl TaxonomyRule {
+    fn matches(&self, title: &str, lower: &str) -> bool {
+        self.keywords.iter().any(|k| contains_word(lower, k))
+            || self.pattern.as_ref().is_some_and(|p| p.is_match(title))
+    }
+}
+
+/// Whether `needle` occurs in `haystack` with no letter or digit on either side.
+fn contains_word(haystack: &str, needle: &str) -> bool {
+    haystack.match_indices(needle).any(|(i, _)| {
+        let before = haystack[..i].chars().next_back();
+        let after = haystack[i + needle.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
+
+/// Load and parse `taxonomy.toml` from the current directory.
+pub fn load_taxonomy() -> Result<Taxonomy> {
+    let raw = fs::read_to_string("taxonomy.toml")
+        .context("Cannot read taxonomy.toml — run from the backend/ directory")?;
+    Taxonomy::from_toml(&raw)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TAXONOMY: &str = r#"
+default_category = "Other"
+
+[[rules]]
+category = "Mobile"
+pattern  = '\b(iOS|iPadOS)\b'
+
+[[rules]]
+category = "Network"
+tags     = ["Router"]
+keywords = ["router", "cisco"]
+"#;
+
+    #[test]
+    fn first_category_wins_and_every_match_tags() {
+        let taxonomy = Taxonomy::from_toml(TAXONOMY).unwrap();
+        let classification = taxonomy.classify("Cisco IOS XE Router RTR STIG");
+        assert_eq!(classification.category, "Network");
+        assert_eq!(classification.tags, ["Network", "Router"]);
+        assert_eq!(classification.source, CategorySource::Taxonomy);
+        assert_eq!(taxonomy.classify("Apple iOS 17 STIG").category, "Mobile");
+        assert_eq!(taxonomy.classify("Kubernetes STIG").category, "Other");
+    }
+
+    #[test]
+    fn keywords_match_whole_words() {
+        let taxonomy = Taxonomy::from_toml(TAXONOMY).unwrap();
+        assert_eq!(taxonomy.classify("Routers and Switches").category, "Other");
+        assert!(contains_word("cisco ios-xe", "cisco"));
+        assert!(!contains_word("franciscosoft", "cisco"));
+    }
+
+    #[test]
+    fn pinned_category_keeps_taxonomy_tags() {
+        let taxonomy = Taxonomy::from_toml(TAXONOMY).unwrap();
+        let classification =
+            taxonomy.classify("Cisco Router").pin("Custom".to_string(), CategorySource::Upload);
+        assert_eq!(classification.category, "Custom");
+        assert_eq!(classification.tags, ["Network", "Router"]);
+        assert_eq!(classification.source, CategorySource::Upload);
+    }
+}
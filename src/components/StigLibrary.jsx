@@ -13,7 +13,6 @@ import TextFilter from "@cloudscape-design/components/text-filter";
 import SpaceBetween from "@cloudscape-design/components/space-between";
 import Box from "@cloudscape-design/components/box";
 import StatusIndicator from "@cloudscape-design/components/status-indicator";
-import SegmentedControl from "@cloudscape-design/components/segmented-control";
 import Pagination from "@cloudscape-design/components/pagination";
 import CollectionPreferences from "@cloudscape-design/components/collection-preferences";
 import Link from "@cloudscape-design/components/link";
@@ -21,8 +20,9 @@ import Container from "@cloudscape-design/components/container";
 import ColumnLayout from "@cloudscape-design/components/column-layout";
 
 const BACKEND = "http://localhost:8080";
-const CATEGORIES = ["Windows", "Linux", "Browser", "Network"];
-const CATEGORY_OPTIONS = CATEGORIES.map((c) => ({ label: c, value: c }));
+// Uploads without a category are classified by the backend's taxonomy.toml
+const AUTO_CATEGORY = { label: "Automatic (from title)", value: "" };
+const ALL_CATEGORIES = { label: "All categories", value: "" };
 
 const CATEGORY_BADGE_COLOR = {
   Windows: "blue",
@@ -87,7 +87,7 @@ export default function StigLibrary({ onLoad, onUploadTab }) {
   // Add-to-library form state (single STIG)
   const [addFiles, setAddFiles] = useState([]);
   const [addId, setAddId] = useState("");
-  const [addCategory, setAddCategory] = useState("Windows");
+  const [addCategory, setAddCategory] = useState("");
   const [addStatus, setAddStatus] = useState("idle");
   const [addResult, setAddResult] = useState(null);
 
@@ -123,10 +123,23 @@ export default function StigLibrary({ onLoad, onUploadTab }) {
 
   const supersededIds = useMemo(() => findSuperseded(catalog), [catalog]);
 
+  const categories = useMemo(
+    () => [...new Set(catalog.map((e) => e.category))].sort(),
+    [catalog],
+  );
+  const categoryOptions = useMemo(
+    () => categories.map((c) => ({ label: c, value: c })),
+    [categories],
+  );
+
   const displayList = useMemo(() => {
     let list = catalog;
     if (categoryFilter)
-      list = list.filter((e) => e.category === categoryFilter);
+      list = list.filter(
+        (e) =>
+          e.category === categoryFilter ||
+          (e.tags ?? []).includes(categoryFilter),
+      );
     if (!showSuperseded) list = list.filter((e) => !supersededIds.has(e.id));
     if (searchText) {
       const term = searchText.toLowerCase();
@@ -203,7 +216,7 @@ export default function StigLibrary({ onLoad, onUploadTab }) {
         const body = new FormData();
         body.append("file", addFiles[0]);
         body.append("id", addId.trim());
-        body.append("category", addCategory);
+        if (addCategory) body.append("category", addCategory);
         const r = await fetch(`${BACKEND}/api/upload`, {
           method: "POST",
           body,
@@ -433,18 +446,16 @@ export default function StigLibrary({ onLoad, onUploadTab }) {
               filteringPlaceholder="Search by title"
               countText={`${displayList.length} matches`}
             />
-            <SegmentedControl
-              selectedId={categoryFilter || "all"}
+            <Select
+              selectedOption={
+                categoryOptions.find((o) => o.value === categoryFilter) ||
+                ALL_CATEGORIES
+              }
               onChange={({ detail }) => {
-                setCategoryFilter(
-                  detail.selectedId === "all" ? null : detail.selectedId,
-                );
+                setCategoryFilter(detail.selectedOption.value || null);
                 setCurrentPage(1);
               }}
-              options={[
-                { text: "All", id: "all" },
-                ...CATEGORIES.map((c) => ({ text: c, id: c })),
-              ]}
+              options={[ALL_CATEGORIES, ...categoryOptions]}
             />
           </SpaceBetween>
         }
@@ -565,13 +576,13 @@ export default function StigLibrary({ onLoad, onUploadTab }) {
                     <FormField label="Category" description="Used for filtering">
                       <Select
                         selectedOption={
-                          CATEGORY_OPTIONS.find((o) => o.value === addCategory) ||
-                          CATEGORY_OPTIONS[0]
+                          categoryOptions.find((o) => o.value === addCategory) ||
+                          AUTO_CATEGORY
                         }
                         onChange={({ detail }) =>
                           setAddCategory(detail.selectedOption.value)
                         }
-                        options={CATEGORY_OPTIONS}
+                        options={[AUTO_CATEGORY, ...categoryOptions]}
                       />
                     </FormField>
                   </ColumnLayout>
@@ -594,7 +605,11 @@ export default function StigLibrary({ onLoad, onUploadTab }) {
                 onDismiss={() => { setLibStatus("idle"); setLibResult(null); }}
               >
                 Imported <strong>{libResult.imported}</strong> STIGs
-                {libResult.errors > 0 && <> ({libResult.errors} skipped)</>}.{" "}
+                {libResult.errors > 0 && <> ({libResult.errors} failed)</>}
+                {libResult.skipped?.length > 0 && (
+                  <>; passed over {libResult.skipped.length} other files</>
+                )}
+                .{" "}
                 <Link onFollow={() => setActiveTab("library")}>
                   View in Library
                 </Link>
@@ -11,7 +11,7 @@ function getAttribute(el, attr) {
 }
 
 function cleanDescription(desc) {
-  return desc
+  return normalizeBlock(desc
     .replace(/<VulnDiscussion>/gi, '')
     .replace(/<\/VulnDiscussion>/gi, '')
     .replace(/<FalsePositives>[\s\S]*?<\/FalsePositives>/gi, '')
@@ -24,8 +24,23 @@ function cleanDescription(desc) {
     .replace(/<MitigationControl>[\s\S]*?<\/MitigationControl>/gi, '')
     .replace(/<Responsibility>[\s\S]*?<\/Responsibility>/gi, '')
     .replace(/<IAControls>[\s\S]*?<\/IAControls>/gi, '')
-    .replace(/<[^>]+>/g, ' ')
-    .replace(/\s+/g, ' ')
+    .replace(/<[^>]+>/g, ' '))
+}
+
+// Keep line structure: drop trailing spaces and the indentation shared by the
+// lines after the first, collapse blank-line runs (mirrors normalize_block in
+// backend/src/parser/mod.rs).
+function normalizeBlock(text) {
+  const lines = text.replace(/\r\n?/g, '\n').split('\n').map((l) => l.trimEnd())
+  const indents = lines
+    .slice(1)
+    .filter((l) => l.length > 0)
+    .map((l) => l.length - l.trimStart().length)
+  const indent = indents.length > 0 ? Math.min(...indents) : 0
+  return lines
+    .map((l, i) => (i === 0 ? l.trimStart() : l.slice(indent)))
+    .join('\n')
+    .replace(/\n{3,}/g, '\n\n')
     .trim()
 }
 
@@ -77,25 +92,33 @@ export function parseXCCDF(xmlText) {
       getTextContent(ruleEl, 'description') ||
       getTextContent(ruleEl, 'Description') ||
       ''
-    const fixText =
+    const fixText = normalizeBlock(
       getTextContent(ruleEl, 'fixtext') ||
       getTextContent(ruleEl, 'fix') ||
       getTextContent(ruleEl, 'Fix') ||
       ''
+    )
 
     let checkText = ''
     const checkContentEls = ruleEl.getElementsByTagName('check-content')
     if (checkContentEls.length > 0) {
-      checkText = checkContentEls[0].textContent?.trim() ?? ''
+      checkText = normalizeBlock(checkContentEls[0].textContent ?? '')
     }
 
     const cciIds = []
+    const legacyIds = []
     for (const identEl of Array.from(ruleEl.getElementsByTagName('ident'))) {
       const val = identEl.textContent?.trim()
-      if (val) cciIds.push(val)
+      if (!val) continue
+      const system = getAttribute(identEl, 'system')
+      if (system.endsWith('/legacy')) legacyIds.push(val)
+      else if (system.endsWith('/cci') || val.startsWith('CCI-')) cciIds.push(val)
     }
 
-    const stigId = groupId || `V-${(100000 + i).toString()}`
+    const stigId =
+      getTextContent(ruleEl, 'version') ||
+      groupId ||
+      `V-${(100000 + i).toString()}`
     // SEVERITY_MAP keys are exactly 'high' | 'medium' | 'low' — no injection risk.
     // eslint-disable-next-line security/detect-object-injection
     const cat = SEVERITY_MAP[severity] ?? 'CAT II'
@@ -110,6 +133,7 @@ export function parseXCCDF(xmlText) {
       fixText,
       checkText,
       cciIds,
+      legacyIds,
       status: 'not_reviewed',
       findingDetails: '',
       comments: '',